@@ -1,10 +1,23 @@
 //! Central module for the [Change] trait and the [ChangeSet].
 use crate::{
-    column::{ColumnAlter, ColumnCreate},
+    column::{varchar, ColumnAlter, ColumnCreate, ColumnType},
+    error::{Result, SqlPressError},
+    expr::Value,
+    index::{
+        index, IndexAddCombinedChange, IndexAttachPartitionChange, IndexDropChange,
+        IndexRenameChange, PrimaryKeyReplaceChange, ReindexChange, ReindexTarget,
+    },
+    naming::NamingStrategy,
+    precondition::{ConditionalChange, Precondition},
     sql_dialect::SqlDialect,
-    table::{Table, TableChange, TableChangeOp},
+    table::{
+        AuditHistoryChange, PartitionAttachChange, PartitionBound, PartitionDetachChange,
+        RowsInsertChange, Table, TableChange, TableChangeOp, TablesDropChange,
+    },
+    types::{DomainCreate, EnumAlter, TypeChange, TypeChangeOp},
+    view::{ViewChange, ViewChangeOp},
 };
-use std::{any::Any, fmt::Debug, rc::Rc};
+use std::{any::Any, fmt::Debug, rc::Rc, time::Duration};
 
 /// Convenience type alias, which holds a list of Changes.
 pub(crate) type Changes = Vec<Box<dyn Change>>;
@@ -28,7 +41,20 @@ impl<T: 'static> ChangeToAny for T {
 pub trait Change: Debug + ChangeToAny {
     /// Convert self-contained structured SQL changes to Data Definition
     /// Language of the given [SqlDialect][crate::sql_dialect::SqlDialect].
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String;
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String>;
+
+    /// Whether executing this change takes locks, which block reads or writes
+    /// on the affected table for a potentially long time.
+    fn is_lock_heavy(&self) -> bool {
+        false
+    }
+
+    /// Whether this change can be executed inside a transaction block.
+    /// Changes like `CREATE INDEX CONCURRENTLY` have to be executed on their
+    /// own.
+    fn is_transactional(&self) -> bool {
+        true
+    }
 }
 
 /// Holds a set of changes, which shall be converted to DDL
@@ -38,6 +64,16 @@ pub struct ChangeSet {
     schema: String,
     /// List of Changes, to be applied within this `ChangeSet`
     changes: Changes,
+    /// Create an index for every foreign key column, which isn't covered by
+    /// an index yet
+    auto_index_foreign_keys: bool,
+    /// Lock timeout, which is set before executing lock heavy changes
+    lock_timeout: Option<Duration>,
+    /// Check, that the extensions of all extension types are created before
+    /// they are used
+    verify_extensions: bool,
+    /// Names constraints and indexes, which are added without a name
+    naming_strategy: Option<Rc<dyn NamingStrategy>>,
 }
 
 impl ChangeSet {
@@ -54,6 +90,118 @@ impl ChangeSet {
         }
     }
 
+    /// Enables the automatic creation of an index (`<table>_<columns>_idx`)
+    /// for the columns of every foreign key, unless all of its columns already
+    /// lead an index, primary key or unique constraint defined within this
+    /// [ChangeSet] (including its nested sets).
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, column::uuid, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.auto_index_foreign_keys(true);
+    /// cs.create_table("order", |t| {
+    ///     t.add_column(uuid("id").primary(true).build());
+    ///     t.add_column(uuid("user_id").build());
+    ///     t.add_foreign_index("user_id", "user", "id", None);
+    /// });
+    ///
+    /// assert!(cs
+    ///     .get_ddl(Postgres::new_rc())
+    ///     .unwrap()
+    ///     .ends_with(r#"CREATE INDEX "order_user_id_idx" ON "public"."order" ("user_id");"#));
+    /// # }
+    /// ```
+    pub fn auto_index_foreign_keys(&mut self, enabled: bool) {
+        self.auto_index_foreign_keys = enabled;
+    }
+
+    /// Enables the check, that every column of an extension type (e.g.
+    /// [citext][crate::column::citext]) is preceded by the
+    /// [ChangeSet::create_extension] of its extension. Rendering the DDL
+    /// fails with [SqlPressError::InvalidChange] otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, column::citext, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.verify_extensions(true);
+    /// cs.create_extension("citext");
+    /// cs.create_table("user", |t| t.add_column(citext("email").build()));
+    ///
+    /// assert_eq!(r#"CREATE EXTENSION IF NOT EXISTS "citext";
+    ///
+    /// CREATE TABLE "public"."user" (
+    /// "email" citext
+    /// );"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn verify_extensions(&mut self, enabled: bool) {
+        self.verify_extensions = enabled;
+    }
+
+    /// Sets the [NamingStrategy], which names foreign keys, inline unique
+    /// constraints and indexes added without an explicit name. Without a
+    /// naming strategy, those are created anonymously and the database
+    /// generates their names.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use std::rc::Rc;
+    /// use sql_press::{
+    ///     change::ChangeSet, column::varchar, naming::DefaultNamingStrategy, sql_dialect::Postgres,
+    /// };
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.naming_strategy(Rc::new(DefaultNamingStrategy::new()));
+    /// cs.alter_table("user", |t| {
+    ///     t.add_column(varchar("email", None).unique(true).build());
+    ///     t.add_foreign_index("tenant_id", "tenant", "id", None);
+    /// });
+    ///
+    /// assert_eq!(r#"ALTER TABLE "public"."user"
+    /// ADD COLUMN "email" VARCHAR CONSTRAINT "uq_user_email" UNIQUE,
+    /// ADD CONSTRAINT "fk_user_tenant_id" FOREIGN KEY("tenant_id") REFERENCES "public"."tenant"("id");"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn naming_strategy(&mut self, strategy: Rc<dyn NamingStrategy>) {
+        self.naming_strategy = Some(strategy);
+    }
+
+    /// Sets a lock timeout (`SET LOCAL lock_timeout` on postgres) before every
+    /// lock heavy change (e.g. `ALTER TABLE`, `DROP TABLE` or `CREATE INDEX`),
+    /// so a change waiting for a lock fails instead of blocking all other
+    /// queries on the table. Failed changes should be retried later.
+    ///
+    /// As `SET LOCAL` only applies to the current transaction, the generated
+    /// DDL has to be executed within a transaction. Changes, which can't run
+    /// inside a transaction (see [Change::is_transactional]), don't get a
+    /// lock timeout. On T-SQL the lock timeout applies to the whole session,
+    /// hence it is reset after the change.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use std::time::Duration;
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.lock_timeout(Duration::from_secs(5));
+    /// cs.drop_table("my_table");
+    ///
+    /// assert_eq!(r#"SET LOCAL lock_timeout = '5000ms';
+    /// DROP TABLE "public"."my_table";"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn lock_timeout(&mut self, timeout: Duration) {
+        self.lock_timeout = Some(timeout);
+    }
+
     /// Add a new `CREATE TABLE` command to the current [ChangeSet] with the
     /// given `name` argument. The `handler` is a closure which adds individual
     /// colum changes to the `CREATE TABLE` command. The `create_table` function
@@ -61,6 +209,11 @@ impl ChangeSet {
     /// - [ColumnAdd::add_column][crate::column::ColumnAdd::add_column],
     /// - [IndexCreate::add_primary_index][crate::index::IndexAlter::add_primary_index],
     /// - [IndexCreate::add_foreign_index][crate::index::IndexAlter::add_foreign_index],
+    /// - [IndexAdd::add_foreign_key][crate::index::IndexAdd::add_foreign_key],
+    /// - [IndexAdd::add_check_constraint][crate::index::IndexAdd::add_check_constraint],
+    /// - [ColumnCreate::column_order],
+    /// - [ColumnCreate::add_enum_column],
+    /// - [ColumnCreate::version_column],
     ///
     /// # Example
     /// ```
@@ -79,14 +232,54 @@ impl ChangeSet {
     where
         H: FnOnce(&mut dyn ColumnCreate),
     {
-        let mut t: Table = Default::default();
+        let mut t = self.table(name);
         handler(&mut t);
-        self.changes.push(TableChange::new(
-            TableChangeOp::Create,
-            self.schema.clone(),
-            name.into(),
-            t.get_changes(),
-        ));
+        let statements = t.take_statements();
+        self.changes.push(
+            TableChange::new(
+                TableChangeOp::Create,
+                self.schema.clone(),
+                name.into(),
+                t.get_changes(),
+            )
+            .with_statements(statements),
+        );
+    }
+
+    /// Same as [ChangeSet::create_table], but renders `CREATE TABLE IF NOT
+    /// EXISTS`, so the table is only created if it doesn't exist yet.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, column::uuid, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.create_table_if_not_exists("settings", |t| {
+    ///     t.add_column(uuid("id").primary(true).build());
+    /// });
+    ///
+    /// assert_eq!(r#"CREATE TABLE IF NOT EXISTS "public"."settings" (
+    /// "id" uuid PRIMARY KEY
+    /// );"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn create_table_if_not_exists<H>(&mut self, name: &str, handler: H)
+    where
+        H: FnOnce(&mut dyn ColumnCreate),
+    {
+        let mut t = self.table(name);
+        handler(&mut t);
+        let statements = t.take_statements();
+        self.changes.push(
+            TableChange::new(
+                TableChangeOp::CreateIfNotExists,
+                self.schema.clone(),
+                name.into(),
+                t.get_changes(),
+            )
+            .with_statements(statements),
+        );
     }
 
     /// Add a new `ALTER TABLE` command to the current [ChangeSet] for the
@@ -97,8 +290,26 @@ impl ChangeSet {
     /// - [ColumnAlter::add_column],
     /// - [ColumnAlter::rename_column],
     /// - [ColumnAlter::alter_column],
+    /// - [ColumnAlter::add_enum_column],
+    /// - [ColumnAlter::version_column],
+    /// - [ColumnAlter::set_column_storage],
+    /// - [ColumnAlter::set_column_compression],
     /// - [IndexAlter::add_primary_index][crate::index::IndexAlter::add_primary_index],
+    /// - [IndexAlter::add_primary_index_named][crate::index::IndexAlter::add_primary_index_named],
     /// - [IndexAlter::add_foreign_index][crate::index::IndexAlter::add_foreign_index],
+    /// - [IndexAlter::add_foreign_key][crate::index::IndexAlter::add_foreign_key],
+    /// - [IndexAlter::add_unique_constraint_using_index][crate::index::IndexAlter::add_unique_constraint_using_index],
+    /// - [IndexAlter::add_primary_index_using_index][crate::index::IndexAlter::add_primary_index_using_index],
+    /// - [IndexAlter::drop_unique_constraint][crate::index::IndexAlter::drop_unique_constraint],
+    /// - [IndexAlter::add_check_constraint][crate::index::IndexAlter::add_check_constraint],
+    /// - [IndexAlter::drop_check_constraint][crate::index::IndexAlter::drop_check_constraint],
+    /// - [IndexAlter::drop_constraint][crate::index::IndexAlter::drop_constraint],
+    /// - [IndexAlter::rename_constraint][crate::index::IndexAlter::rename_constraint],
+    /// - [IndexAlter::drop_primary_key][crate::index::IndexAlter::drop_primary_key],
+    /// - [IndexAlter::validate_constraint][crate::index::IndexAlter::validate_constraint],
+    /// - [IndexAlter::add_index][crate::index::IndexAlter::add_index],
+    /// - [IndexAlter::drop_index][crate::index::IndexAlter::drop_index],
+    /// - [IndexAlter::drop_index_with][crate::index::IndexAlter::drop_index_with],
     /// - [ColumnDrop::drop_column][crate::column::ColumnDrop::drop_column],
     /// - [ColumnDrop::drop_column_if_exists][crate::column::ColumnDrop::drop_column_if_exists].
     ///
@@ -118,14 +329,53 @@ impl ChangeSet {
     where
         H: FnOnce(&mut dyn ColumnAlter),
     {
-        let mut t: Table = Default::default();
+        let mut t = self.table(name);
         handler(&mut t);
-        self.changes.push(TableChange::new(
-            TableChangeOp::Alter,
-            self.schema.clone(),
-            name.into(),
-            t.get_changes(),
-        ));
+        let statements = t.take_statements();
+        self.changes.push(
+            TableChange::new(
+                TableChangeOp::Alter,
+                self.schema.clone(),
+                name.into(),
+                t.get_changes(),
+            )
+            .with_statements(statements),
+        );
+    }
+
+    /// Same as [ChangeSet::alter_table], but renders `ALTER TABLE IF EXISTS`,
+    /// so the changes are skipped if the table doesn't exist.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.alter_table_if_exists("legacy_table", |t| {
+    ///     t.drop_column_if_exists("obsolete");
+    /// });
+    ///
+    /// assert_eq!(r#"ALTER TABLE IF EXISTS "public"."legacy_table"
+    /// DROP COLUMN IF EXISTS "obsolete";"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn alter_table_if_exists<H>(&mut self, name: &str, handler: H)
+    where
+        H: FnOnce(&mut dyn ColumnAlter),
+    {
+        let mut t = self.table(name);
+        handler(&mut t);
+        let statements = t.take_statements();
+        self.changes.push(
+            TableChange::new(
+                TableChangeOp::AlterIfExists,
+                self.schema.clone(),
+                name.into(),
+                t.get_changes(),
+            )
+            .with_statements(statements),
+        );
     }
 
     /// Add a new `DROP TABLE` command to the current [ChangeSet] for the given
@@ -139,14 +389,141 @@ impl ChangeSet {
     /// cs.drop_table("my_table");
     /// ```
     pub fn drop_table(&mut self, name: &str) {
+        self.push_drop_table(name, false, false)
+    }
+
+    /// Same as [ChangeSet::drop_table], but renders `DROP TABLE IF EXISTS`,
+    /// so the command doesn't fail if the table is already gone.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_table_if_exists("my_table");
+    ///
+    /// assert_eq!(r#"DROP TABLE IF EXISTS "public"."my_table";"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn drop_table_if_exists(&mut self, name: &str) {
+        self.push_drop_table(name, true, false)
+    }
+
+    /// Add a new `DROP TABLE ... CASCADE` command to the current [ChangeSet],
+    /// which also drops dependent objects like views and foreign keys of
+    /// other tables. Optionally renders `IF EXISTS`.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_table_cascade("my_table", true);
+    ///
+    /// assert_eq!(
+    ///     r#"DROP TABLE IF EXISTS "public"."my_table" CASCADE;"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn drop_table_cascade(&mut self, name: &str, if_exists: bool) {
+        self.push_drop_table(name, if_exists, true)
+    }
+
+    fn push_drop_table(&mut self, name: &str, if_exists: bool, cascade: bool) {
         self.changes.push(TableChange::new(
-            TableChangeOp::Drop,
+            TableChangeOp::Drop { if_exists, cascade },
             self.schema.clone(),
             name.into(),
             Vec::new(),
         ))
     }
 
+    /// Add a single `DROP TABLE` command for all given tables to the current
+    /// [ChangeSet], optionally dropping dependent objects via `CASCADE`.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_tables(&["search_index", "search_term"], true);
+    ///
+    /// assert_eq!(
+    ///     r#"DROP TABLE "public"."search_index", "public"."search_term" CASCADE;"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn drop_tables(&mut self, names: &[&str], cascade: bool) {
+        self.changes
+            .push(Box::new(TablesDropChange::new(names, cascade)))
+    }
+
+    /// Add a new `ALTER TABLE ... ATTACH PARTITION` command to the current
+    /// [ChangeSet], which attaches the existing table `partition_name` as
+    /// partition with the given bound to the partitioned table `name`.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, expr::Expr, sql_dialect::Postgres, table::PartitionBound};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.attach_partition(
+    ///     "measurement",
+    ///     "measurement_y2024",
+    ///     PartitionBound::Range {
+    ///         from: vec![Expr::val("2024-01-01")],
+    ///         to: vec![Expr::val("2025-01-01")],
+    ///     },
+    /// );
+    ///
+    /// assert_eq!(
+    ///     r#"ALTER TABLE "public"."measurement" ATTACH PARTITION "public"."measurement_y2024" FOR VALUES FROM ('2024-01-01') TO ('2025-01-01');"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn attach_partition(&mut self, name: &str, partition_name: &str, bound: PartitionBound) {
+        self.changes.push(Box::new(PartitionAttachChange::new(
+            name,
+            partition_name,
+            bound,
+        )))
+    }
+
+    /// Add a new `ALTER TABLE ... DETACH PARTITION` command to the current
+    /// [ChangeSet]. The detached partition keeps existing as a regular table.
+    ///
+    /// Detaching a partition concurrently doesn't block queries on the
+    /// partitioned table, but must not be executed inside a transaction.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.detach_partition("measurement", "measurement_y2023", true);
+    ///
+    /// assert_eq!(
+    ///     r#"ALTER TABLE "public"."measurement" DETACH PARTITION "public"."measurement_y2023" CONCURRENTLY;"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn detach_partition(&mut self, name: &str, partition_name: &str, concurrently: bool) {
+        self.changes.push(Box::new(PartitionDetachChange::new(
+            name,
+            partition_name,
+            concurrently,
+        )))
+    }
+
     /// Add a new `ALTER TABLE ... RENAME TO ...` command to the current
     /// [ChangeSet] for the given table name.
     ///
@@ -168,126 +545,2159 @@ impl ChangeSet {
         ))
     }
 
-    /// Adds a plain string Change to the current [ChangeSet]. This string is
-    /// executed with no transformation etc. This means the script which is run
-    /// is potentially bound to a specific database type (e.g. postgres, mysql, ...);
+    /// Add a new `CREATE TYPE ... AS (...)` command to the current [ChangeSet]
+    /// for a composite type with the given fields. Columns of this type can be
+    /// added with [composite][crate::column::composite].
     ///
     /// # Example
     /// ```
-    /// use sql_press::change::ChangeSet;
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{
+    ///     change::ChangeSet,
+    ///     column::{composite, uuid, ColumnType},
+    ///     sql_dialect::Postgres,
+    /// };
     ///
     /// let mut cs = ChangeSet::new();
-    /// cs.run_script("CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\";");
+    /// cs.create_composite_type(
+    ///     "address",
+    ///     vec![("street", ColumnType::TEXT), ("zip", ColumnType::VARCHAR(Some(10)))],
+    /// );
+    /// cs.create_table("customer", |t| {
+    ///     t.add_column(uuid("id").primary(true).build());
+    ///     t.add_column(composite("address", "address").build());
+    /// });
+    ///
+    /// assert_eq!(r#"CREATE TYPE "public"."address" AS (
+    /// "street" text,
+    /// "zip" VARCHAR(10)
+    /// );
+    ///
+    /// CREATE TABLE "public"."customer" (
+    /// "id" uuid PRIMARY KEY,
+    /// "address" "public"."address"
+    /// );"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
     /// ```
-    pub fn run_script(&mut self, script: &str) {
-        self.changes.push(Box::new(Script::new(script)))
+    pub fn create_composite_type(&mut self, name: &str, fields: Vec<(&str, ColumnType)>) {
+        self.changes.push(Box::new(TypeChange::new(
+            TypeChangeOp::CreateComposite {
+                fields: fields
+                    .into_iter()
+                    .map(|(n, ct)| (n.to_string(), ct))
+                    .collect(),
+            },
+            name,
+        )))
     }
 
-    /// Generates DDL for the given [SqlDialect] recursively for all changes in
-    /// the current [ChangeSet].
+    /// Add a new `CREATE DOMAIN` command to the current [ChangeSet], which
+    /// defines a reusable type based on `ct` with the given constraints.
     ///
     /// # Example
     /// ```
-    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{
+    ///     change::ChangeSet,
+    ///     column::{domain, ColumnType},
+    ///     sql_dialect::Postgres,
+    /// };
     ///
     /// let mut cs = ChangeSet::new();
-    /// cs.drop_table("my_table");
-    /// cs.run_script("DDL INSTRUCTION;");
+    /// cs.create_domain("slug", ColumnType::TEXT, |d| d.check("VALUE ~ '^[a-z0-9-]+$'"));
+    /// cs.create_table("article", |t| t.add_column(domain("slug", "slug").build()));
     ///
-    /// assert_eq!(r#"DROP TABLE public."my_table";
+    /// assert_eq!(r#"CREATE DOMAIN "public"."slug" AS text CHECK (VALUE ~ '^[a-z0-9-]+$');
     ///
-    /// DDL INSTRUCTION;
-    /// "#, cs.get_ddl(Postgres::new_rc()));
+    /// CREATE TABLE "public"."article" (
+    /// "slug" "public"."slug"
+    /// );"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
     /// ```
-    pub fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
+    pub fn create_domain<H>(&mut self, name: &str, ct: ColumnType, handler: H)
+    where
+        H: FnOnce(&mut DomainCreate),
+    {
+        let mut domain = DomainCreate::default();
+        handler(&mut domain);
+
+        self.changes.push(Box::new(TypeChange::new(
+            TypeChangeOp::CreateDomain { ct, domain },
+            name,
+        )))
+    }
+
+    /// Add a new `DROP DOMAIN` command to the current [ChangeSet].
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::change::ChangeSet;
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_domain("slug");
+    /// ```
+    pub fn drop_domain(&mut self, name: &str) {
         self.changes
-            .iter()
-            .map(|c| c.get_ddl(dialect.clone()))
-            .collect::<Vec<String>>()
-            .join("\n\n")
+            .push(Box::new(TypeChange::new(TypeChangeOp::DropDomain, name)))
     }
-}
 
-/// Plain change which is run on the database without additional transformation.
-#[derive(Debug)]
-pub struct Script {
-    script: String,
-}
+    /// Adds values to an existing enum type (`ALTER TYPE ... ADD VALUE`).
+    ///
+    /// Postgres before version 12 can't add enum values inside a transaction
+    /// block, so the generated DDL has to be executed on its own there.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.alter_enum("status", |e| {
+    ///     e.add_value("archived").before("published");
+    ///     e.add_value("deleted");
+    /// });
+    ///
+    /// assert_eq!(r#"ALTER TYPE "public"."status" ADD VALUE IF NOT EXISTS 'archived' BEFORE 'published';
+    ///
+    /// ALTER TYPE "public"."status" ADD VALUE IF NOT EXISTS 'deleted';"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn alter_enum<H>(&mut self, name: &str, handler: H)
+    where
+        H: FnOnce(&mut EnumAlter),
+    {
+        let mut e = EnumAlter::default();
+        handler(&mut e);
 
-impl Script {
-    pub fn new(script: &str) -> Self {
-        Self {
-            script: script.into(),
+        for v in e.values {
+            self.changes.push(Box::new(TypeChange::new(
+                TypeChangeOp::AddValue {
+                    value: v.value,
+                    position: v.position,
+                },
+                name,
+            )))
         }
     }
-}
 
-impl Change for Script {
-    fn get_ddl(&self, _dialect: Rc<dyn SqlDialect>) -> String {
-        format!("{}\n", self.script)
+    /// Add a new `ALTER TYPE ... RENAME TO ...` command to the current
+    /// [ChangeSet] for the given user-defined type.
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::change::ChangeSet;
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.rename_type("address", "postal_address");
+    /// ```
+    pub fn rename_type(&mut self, name: &str, new_name: &str) {
+        self.changes.push(Box::new(TypeChange::new(
+            TypeChangeOp::Rename {
+                new_type_name: new_name.into(),
+            },
+            name,
+        )))
     }
-}
 
-impl Default for ChangeSet {
-    fn default() -> Self {
-        Self {
-            schema: "public".into(),
-            changes: Default::default(),
-        }
+    /// Add a new `DROP TYPE` command to the current [ChangeSet] for the given
+    /// user-defined type.
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::change::ChangeSet;
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_type("address");
+    /// ```
+    pub fn drop_type(&mut self, name: &str) {
+        self.changes
+            .push(Box::new(TypeChange::new(TypeChangeOp::Drop, name)))
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::{
-        column::{uuid, varchar, ColumnType, DefaultConstraint},
-        sql_dialect::postgres::Postgres,
-    };
-
-    use super::*;
-
-    #[test]
-    fn create_table() {
-        let mut cs = ChangeSet::new();
 
-        cs.create_table("xxx", |t| {
-            t.add_foreign_index("tag_id", "tag", "id", Some("fk_blubbi".into()));
-            t.add_column(
-                uuid("id")
-                    .primary(true)
-                    .default(DefaultConstraint::Plain("uuid_generate_v4()".into()))
-                    .build(),
-            );
-            t.add_column(varchar("description", None).build());
-            t.add_primary_index(vec!["id", "id2"]);
+    /// Creates a lookup table with a `code` primary key and a `name` column and
+    /// seeds it with the given code/name pairs.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.create_lookup_table("countries", &[("US", "United States"), ("DE", "Germany")]);
+    ///
+    /// assert_eq!(r#"CREATE TABLE "public"."countries" (
+    /// "code" VARCHAR(64) PRIMARY KEY,
+    /// "name" VARCHAR(255) NOT NULL
+    /// );
+    ///
+    /// INSERT INTO "public"."countries" ("code", "name") VALUES
+    /// ('US', 'United States'),
+    /// ('DE', 'Germany');"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn create_lookup_table(&mut self, name: &str, rows: &[(&str, &str)]) {
+        self.create_table(name, |t| {
+            t.add_column(varchar("code", Some(64)).primary(true).build());
+            t.add_column(varchar("name", Some(255)).not_null(true).build());
         });
 
-        let _d = Rc::new(Postgres::new());
-        // println!("{}", cs.get_ddl(_d));
+        if !rows.is_empty() {
+            self.changes.push(Box::new(RowsInsertChange::new(
+                name,
+                &["code", "name"],
+                rows.iter()
+                    .map(|(code, name)| vec![Value::from(*code), Value::from(*name)])
+                    .collect(),
+            )))
+        }
     }
 
-    #[test]
+    /// Adds an audit history for the given table: a `{table}_history` table
+    /// with the same columns plus `operation`, `changed_at` and `changed_by`,
+    /// and a trigger which records every inserted, updated and deleted row.
+    /// The columns are derived from the changes of the table within this
+    /// [ChangeSet], so they stay in sync with the table definition.
+    ///
+    /// Rendering the DDL fails with [SqlPressError::InvalidChange], if the
+    /// table is not created within this [ChangeSet] (or one of its nested
+    /// sets) before.
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::{
+    ///     change::ChangeSet,
+    ///     column::{integer, uuid},
+    /// };
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.create_table("orders", |t| {
+    ///     t.add_column(uuid("id").primary(true).build());
+    ///     t.add_column(integer("total").build());
+    /// });
+    /// cs.add_audit_history("orders");
+    /// ```
+    pub fn add_audit_history(&mut self, table_name: &str) {
+        let columns = self.table_columns(table_name);
+        self.changes
+            .push(Box::new(AuditHistoryChange::new(table_name, columns)))
+    }
+
+    /// Columns (name and type) of the given table after all previous changes
+    /// of this [ChangeSet], if the table is created within it.
+    fn table_columns(&self, table_name: &str) -> Option<Vec<(String, ColumnType)>> {
+        let mut tables: Vec<(String, Vec<(String, ColumnType)>)> = Vec::new();
+        for c in self.flattened_changes() {
+            let Some(t) = c.as_any().downcast_ref::<TableChange>() else {
+                continue;
+            };
+
+            match tables.iter_mut().find(|(n, _)| *n == t.name) {
+                Some((name, columns)) => {
+                    t.apply_columns(columns);
+                    if let Some(new_name) = t.new_name() {
+                        *name = new_name.into();
+                    }
+                }
+                None if t.is_create() => {
+                    let mut columns = Vec::new();
+                    t.apply_columns(&mut columns);
+                    tables.push((t.name.clone(), columns));
+                }
+                None => {}
+            }
+        }
+
+        tables
+            .into_iter()
+            .find(|(n, columns)| n == table_name && !columns.is_empty())
+            .map(|(_, columns)| columns)
+    }
+
+    /// Add a new `ALTER VIEW ... RENAME TO ...` command to the current
+    /// [ChangeSet] for the given view name.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.rename_view("active_users", "active_customers");
+    ///
+    /// assert_eq!(
+    ///     r#"ALTER VIEW "public"."active_users" RENAME TO "active_customers";"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn rename_view(&mut self, name: &str, new_name: &str) {
+        self.changes.push(Box::new(ViewChange::new(
+            ViewChangeOp::Rename {
+                new_view_name: new_name.into(),
+            },
+            name,
+        )))
+    }
+
+    /// Changes the owner of the given view to `role`.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.alter_view_owner("active_users", "reporting");
+    ///
+    /// assert_eq!(
+    ///     r#"ALTER VIEW "public"."active_users" OWNER TO "reporting";"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn alter_view_owner(&mut self, name: &str, role: &str) {
+        self.changes.push(Box::new(ViewChange::new(
+            ViewChangeOp::Owner { role: role.into() },
+            name,
+        )))
+    }
+
+    /// Sets options (e.g. `security_barrier` or `check_option`) of the given
+    /// view.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.alter_view_set_options("active_users", &[("security_barrier", "true")]);
+    ///
+    /// assert_eq!(
+    ///     r#"ALTER VIEW "public"."active_users" SET (security_barrier = true);"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn alter_view_set_options(&mut self, name: &str, options: &[(&str, &str)]) {
+        self.changes.push(Box::new(ViewChange::new(
+            ViewChangeOp::SetOptions {
+                options: options
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            },
+            name,
+        )))
+    }
+
+    /// Add a new `CREATE INDEX` command to the current [ChangeSet]. The index
+    /// is defined via [index][crate::index::index].
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, index::index, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.create_index(index("users", vec!["email"]).fillfactor(70).build());
+    ///
+    /// assert_eq!(
+    ///     r#"CREATE INDEX ON "public"."users" ("email") WITH (fillfactor = 70);"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn create_index(&mut self, index: IndexAddCombinedChange) {
+        let mut index = index;
+        if let (None, Some(n)) = (&index.idx_name, &self.naming_strategy) {
+            index.idx_name = Some(n.index(&index.table_name, &index.column_names()));
+        }
+        self.changes.push(Box::new(index))
+    }
+
+    /// Add a new `ALTER INDEX ... RENAME TO ...` command to the current
+    /// [ChangeSet].
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.rename_index("users_email_idx", "idx_users_email");
+    ///
+    /// assert_eq!(
+    ///     r#"ALTER INDEX "public"."users_email_idx" RENAME TO "idx_users_email";"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn rename_index(&mut self, index_name: &str, new_index_name: &str) {
+        self.changes
+            .push(Box::new(IndexRenameChange::new(index_name, new_index_name)))
+    }
+
+    /// Add a new `ALTER INDEX ... ATTACH PARTITION` command to the current
+    /// [ChangeSet], which attaches the index of a partition to the index of
+    /// its partitioned parent table (created with
+    /// [IndexBuilder::only][crate::index::IndexBuilder::only]).
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, index::index, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.create_index(
+    ///     index("measurement", vec!["logdate"])
+    ///         .name("measurement_logdate_idx")
+    ///         .only(true)
+    ///         .build(),
+    /// );
+    /// cs.create_index(
+    ///     index("measurement_y2024", vec!["logdate"])
+    ///         .name("measurement_y2024_logdate_idx")
+    ///         .build(),
+    /// );
+    /// cs.attach_index_partition("measurement_logdate_idx", "measurement_y2024_logdate_idx");
+    ///
+    /// assert_eq!(r#"CREATE INDEX "measurement_logdate_idx" ON ONLY "public"."measurement" ("logdate");
+    ///
+    /// CREATE INDEX "measurement_y2024_logdate_idx" ON "public"."measurement_y2024" ("logdate");
+    ///
+    /// ALTER INDEX "public"."measurement_logdate_idx" ATTACH PARTITION "public"."measurement_y2024_logdate_idx";"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn attach_index_partition(&mut self, index_name: &str, partition_index_name: &str) {
+        self.changes.push(Box::new(IndexAttachPartitionChange::new(
+            index_name,
+            partition_index_name,
+        )))
+    }
+
+    /// Add a new `DROP INDEX` command to the current [ChangeSet], which drops
+    /// the given index by its name. Within [ChangeSet::alter_table] use
+    /// [IndexAlter::drop_index][crate::index::IndexAlter::drop_index] instead,
+    /// which is required by dialects, which need the table of the index.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_index("idx_users_email");
+    ///
+    /// assert_eq!(r#"DROP INDEX "public"."idx_users_email";"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn drop_index(&mut self, index_name: &str) {
+        self.drop_index_with(index_name, false, false)
+    }
+
+    /// Same as [ChangeSet::drop_index], but optionally renders `IF EXISTS`
+    /// and `CONCURRENTLY`. Dropping an index concurrently must not be executed
+    /// inside a transaction.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_index_with("idx_users_email", true, true);
+    ///
+    /// assert_eq!(
+    ///     r#"DROP INDEX CONCURRENTLY IF EXISTS "public"."idx_users_email";"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn drop_index_with(&mut self, index_name: &str, if_exists: bool, concurrently: bool) {
+        self.changes.push(Box::new(IndexDropChange::new(
+            index_name,
+            if_exists,
+            concurrently,
+        )))
+    }
+
+    /// Add a new `REINDEX INDEX` command to the current [ChangeSet], which
+    /// rebuilds the given index, optionally without locking out writes.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.reindex_index("idx_users_email", true);
+    ///
+    /// assert_eq!(
+    ///     r#"REINDEX INDEX CONCURRENTLY "public"."idx_users_email";"#,
+    ///     cs.get_ddl(Postgres::new_rc()).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn reindex_index(&mut self, name: &str, concurrently: bool) {
+        self.changes.push(Box::new(ReindexChange::new(
+            name,
+            ReindexTarget::Index { concurrently },
+        )))
+    }
+
+    /// Add a new `REINDEX TABLE` command to the current [ChangeSet], which
+    /// rebuilds all indexes of the given table.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.reindex_table("users");
+    ///
+    /// assert_eq!(r#"REINDEX TABLE "public"."users";"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn reindex_table(&mut self, name: &str) {
+        self.changes
+            .push(Box::new(ReindexChange::new(name, ReindexTarget::Table)))
+    }
+
+    /// Replaces the primary key of the given table with one on `columns`
+    /// without locking the table while the new index is built. This expands
+    /// into the following steps:
+    /// 1. `CREATE UNIQUE INDEX CONCURRENTLY` on the new primary key columns,
+    /// 2. drop the old primary key constraint and add the new one with the
+    ///    same name `USING INDEX` within a single `ALTER TABLE`.
+    ///
+    /// `constraint_name` is the name of the existing primary key constraint,
+    /// which defaults to `<table>_pkey`.
+    ///
+    /// As the index is built concurrently, this change must not be executed
+    /// inside a transaction. Foreign keys, which reference the old primary
+    /// key, depend on its index and prevent dropping it. They have to be
+    /// dropped before and recreated (on the new key columns) after this
+    /// change.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.replace_primary_key_concurrently("orders", vec!["tenant_id", "id"], None);
+    ///
+    /// assert_eq!(r#"CREATE UNIQUE INDEX CONCURRENTLY "orders_pkey_new" ON "public"."orders" ("tenant_id", "id");
+    ///
+    /// ALTER TABLE "public"."orders"
+    /// DROP CONSTRAINT "orders_pkey",
+    /// ADD CONSTRAINT "orders_pkey" PRIMARY KEY USING INDEX "orders_pkey_new";"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn replace_primary_key_concurrently(
+        &mut self,
+        name: &str,
+        columns: Vec<&str>,
+        constraint_name: Option<&str>,
+    ) {
+        self.changes.push(Box::new(PrimaryKeyReplaceChange::new(
+            name,
+            columns,
+            constraint_name,
+        )))
+    }
+
+    /// Adds the changes defined in `handler` to the current [ChangeSet], which
+    /// are only executed if the given [Precondition] holds. This allows
+    /// consolidating environments with slightly different histories.
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::{change::ChangeSet, column::text, precondition::Precondition};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.only_if(Precondition::ColumnMissing("tags".into(), "slug".into()), |cs| {
+    ///     cs.alter_table("tags", |t| {
+    ///         t.add_column(text("slug").build());
+    ///     });
+    /// });
+    /// ```
+    pub fn only_if<H>(&mut self, precondition: Precondition, handler: H)
+    where
+        H: FnOnce(&mut ChangeSet),
+    {
+        let mut cs = self.nested();
+        handler(&mut cs);
+        self.changes
+            .push(Box::new(ConditionalChange::new(precondition, cs)))
+    }
+
+    /// Adds the changes defined in `handler` to the current [ChangeSet] and
+    /// tags them with the given `tags`. Tagged changes can be selectively
+    /// rendered with [ChangeSet::get_ddl_for_tags], e.g. for optional feature
+    /// schemas, which only some installations receive.
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::{change::ChangeSet, column::{text, uuid}};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.tagged(&["search"], |cs| {
+    ///     cs.create_table("search_term", |t| {
+    ///         t.add_column(uuid("id").primary(true).build());
+    ///         t.add_column(text("term").build());
+    ///     });
+    /// });
+    /// ```
+    pub fn tagged<H>(&mut self, tags: &[&str], handler: H)
+    where
+        H: FnOnce(&mut ChangeSet),
+    {
+        let mut cs = self.nested();
+        handler(&mut cs);
+        self.changes.push(Box::new(TaggedChanges {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            changes: cs,
+        }))
+    }
+
+    /// Adds the changes defined in `handler` to the current [ChangeSet] and
+    /// restricts them to the given environment `contexts`, e.g. for seed data
+    /// which must only be applied in development. Use
+    /// [ChangeSet::get_ddl_for_context] to render the changes of a context.
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::change::ChangeSet;
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.contexts(&["dev", "staging"], |cs| {
+    ///     cs.run_script("INSERT INTO \"public\".\"user\" (\"name\") VALUES ('admin');");
+    /// });
+    /// ```
+    pub fn contexts<H>(&mut self, contexts: &[&str], handler: H)
+    where
+        H: FnOnce(&mut ChangeSet),
+    {
+        let mut cs = self.nested();
+        handler(&mut cs);
+        self.changes.push(Box::new(ContextChanges {
+            contexts: contexts.iter().map(|c| c.to_string()).collect(),
+            changes: cs,
+        }))
+    }
+
+    /// Table, which inherits the settings of this [ChangeSet].
+    fn table(&self, name: &str) -> Table {
+        let mut t = Table::named(name);
+        t.naming_strategy = self.naming_strategy.clone();
+        t
+    }
+
+    /// Changes of this [ChangeSet] in order, with nested sets (e.g. of
+    /// [ChangeSet::tagged] or [ChangeSet::only_if]) replaced by their changes.
+    fn flattened_changes(&self) -> Vec<&dyn Change> {
+        let mut changes = Vec::new();
+        for c in self.changes.iter() {
+            match Self::nested_set(c.as_ref()) {
+                Some(nested) => changes.extend(nested.flattened_changes()),
+                None => changes.push(c.as_ref()),
+            }
+        }
+
+        changes
+    }
+
+    /// The nested [ChangeSet] of the change, if it is one of the grouping
+    /// changes (e.g. of [ChangeSet::tagged] or [ChangeSet::only_if]).
+    fn nested_set(c: &dyn Change) -> Option<&ChangeSet> {
+        let any = c.as_any();
+        if let Some(t) = any.downcast_ref::<TaggedChanges>() {
+            Some(&t.changes)
+        } else if let Some(t) = any.downcast_ref::<ContextChanges>() {
+            Some(&t.changes)
+        } else {
+            any.downcast_ref::<ConditionalChange>().map(|t| t.changes())
+        }
+    }
+
+    /// Creates an empty [ChangeSet] with the same settings as the current one.
+    fn nested(&self) -> ChangeSet {
+        ChangeSet {
+            schema: self.schema.clone(),
+            auto_index_foreign_keys: self.auto_index_foreign_keys,
+            lock_timeout: self.lock_timeout,
+            // nested sets are verified together with their parent
+            verify_extensions: false,
+            naming_strategy: self.naming_strategy.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Add a new `CREATE EXTENSION IF NOT EXISTS` command to the current
+    /// [ChangeSet].
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::change::ChangeSet;
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.create_extension("hstore");
+    /// ```
+    pub fn create_extension(&mut self, name: &str) {
+        self.changes
+            .push(Box::new(ExtensionChange { name: name.into() }))
+    }
+
+    /// Adds the prerequisites (e.g. extensions), which the dialect needs to
+    /// generate random UUIDs for [DefaultConstraint::RandomUuid][crate::column::DefaultConstraint::RandomUuid].
+    /// Nothing is rendered if the database supports it out of the box.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{
+    ///     change::ChangeSet,
+    ///     column::{uuid, DefaultConstraint},
+    ///     sql_dialect::Postgres,
+    /// };
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.ensure_uuid_generation();
+    /// cs.create_table("tag", |t| {
+    ///     t.add_column(uuid("id").primary(true).default(DefaultConstraint::RandomUuid).build());
+    /// });
+    ///
+    /// assert_eq!(r#"CREATE EXTENSION IF NOT EXISTS "pgcrypto";
+    ///
+    /// CREATE TABLE "public"."tag" (
+    /// "id" uuid PRIMARY KEY DEFAULT gen_random_uuid()
+    /// );"#, cs.get_ddl(std::rc::Rc::new(Postgres::new().with_version(12))).unwrap());
+    /// # }
+    /// ```
+    pub fn ensure_uuid_generation(&mut self) {
+        self.changes.push(Box::new(UuidGenerationChange))
+    }
+
+    /// Adds a plain string Change to the current [ChangeSet]. This string is
+    /// executed with no transformation etc. This means the script which is run
+    /// is potentially bound to a specific database type (e.g. postgres, mysql, ...);
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::change::ChangeSet;
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.run_script("CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\";");
+    /// ```
+    pub fn run_script(&mut self, script: &str) {
+        self.changes.push(Box::new(Script::new(script)))
+    }
+
+    /// Whether all changes of the current [ChangeSet] can be executed within a
+    /// single transaction (see [Change::is_transactional]).
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::{change::ChangeSet, index::index};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.create_index(index("users", vec!["email"]).build());
+    /// assert!(cs.is_transactional());
+    ///
+    /// cs.create_index(index("users", vec!["name"]).concurrently(true).build());
+    /// assert!(!cs.is_transactional());
+    /// ```
+    pub fn is_transactional(&self) -> bool {
+        self.changes.iter().all(|c| c.is_transactional())
+    }
+
+    /// Generates DDL for the given [SqlDialect] recursively for all changes in
+    /// the current [ChangeSet]. Fails with a [SqlPressError], if a change is
+    /// not supported by the dialect or can't be rendered.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_table("my_table");
+    /// cs.run_script("DDL INSTRUCTION;");
+    ///
+    /// assert_eq!(r#"DROP TABLE "public"."my_table";
+    ///
+    /// DDL INSTRUCTION;
+    /// "#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    ///
+    /// cs.drop_table(&"t".repeat(64));
+    /// assert!(matches!(
+    ///     cs.get_ddl(Postgres::new_rc()),
+    ///     Err(sql_press::error::SqlPressError::InvalidChange(_))
+    /// ));
+    /// # }
+    /// ```
+    pub fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        self.get_ddl_filtered(dialect, RenderFilter::default(), &[])
+    }
+
+    /// Renders every change with each of the given dialects and reports the
+    /// changes, which are not supported by a dialect or are raw scripts (see
+    /// [ChangeSet::run_script]) and therefore not translated at all. Issues
+    /// reference dialects by their name (see [SqlDialect::name]) and changes
+    /// by their position. Raw scripts within nested sets (e.g. of
+    /// [ChangeSet::tagged]) are reported with the position of the nested set.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{
+    ///     change::{ChangeSet, PortabilityIssueKind},
+    ///     sql_dialect::Postgres,
+    /// };
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_table("a");
+    /// cs.run_script("VACUUM;");
+    ///
+    /// let report = cs.portability_report(&[Postgres::new_rc()]);
+    /// assert!(!report.is_portable());
+    /// assert_eq!(report.issues[0].dialect, "postgres");
+    /// assert_eq!(report.issues[0].change, 1);
+    /// assert_eq!(report.issues[0].kind, PortabilityIssueKind::RawScript);
+    /// # }
+    /// ```
+    pub fn portability_report(&self, dialects: &[Rc<dyn SqlDialect>]) -> PortabilityReport {
+        let mut issues = Vec::new();
+
+        for dialect in dialects.iter() {
+            for (c, change) in self.changes.iter().enumerate() {
+                let is_script = |c: &dyn Change| c.as_any().is::<Script>();
+                let raw = match Self::nested_set(change.as_ref()) {
+                    Some(nested) => nested.flattened_changes().into_iter().any(is_script),
+                    None => is_script(change.as_ref()),
+                };
+                let kind = if raw {
+                    Some(PortabilityIssueKind::RawScript)
+                } else {
+                    change
+                        .get_ddl(dialect.clone())
+                        .err()
+                        .map(PortabilityIssueKind::Unsupported)
+                };
+
+                if let Some(kind) = kind {
+                    issues.push(PortabilityIssue {
+                        dialect: dialect.name(),
+                        change: c,
+                        kind,
+                    });
+                }
+            }
+        }
+
+        PortabilityReport { issues }
+    }
+
+    /// Generates DDL like [ChangeSet::get_ddl], but only includes tagged
+    /// changes (see [ChangeSet::tagged]) if one of their tags is contained in
+    /// `tags`. Untagged changes are always included.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_table("a");
+    /// cs.tagged(&["search"], |cs| cs.drop_table("b"));
+    /// cs.tagged(&["billing"], |cs| cs.drop_table("c"));
+    ///
+    /// assert_eq!(r#"DROP TABLE "public"."a";
+    ///
+    /// DROP TABLE "public"."b";"#, cs.get_ddl_for_tags(Postgres::new_rc(), &["search"]).unwrap());
+    /// # }
+    /// ```
+    pub fn get_ddl_for_tags(&self, dialect: Rc<dyn SqlDialect>, tags: &[&str]) -> Result<String> {
+        self.get_ddl_filtered(
+            dialect,
+            RenderFilter {
+                tags: Some(tags),
+                ..Default::default()
+            },
+            &[],
+        )
+    }
+
+    /// Generates DDL like [ChangeSet::get_ddl] for the given environment
+    /// `context`. Changes restricted to contexts (see [ChangeSet::contexts])
+    /// are only included if `context` is one of them. Unrestricted changes
+    /// are always included.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_table("a");
+    /// cs.contexts(&["dev", "staging"], |cs| cs.run_script("DELETE FROM \"public\".\"b\";"));
+    ///
+    /// assert_eq!(r#"DROP TABLE "public"."a";"#, cs.get_ddl_for_context(Postgres::new_rc(), "prod").unwrap());
+    /// # }
+    /// ```
+    pub fn get_ddl_for_context(
+        &self,
+        dialect: Rc<dyn SqlDialect>,
+        context: &str,
+    ) -> Result<String> {
+        self.get_ddl_filtered(
+            dialect,
+            RenderFilter {
+                context: Some(context),
+                ..Default::default()
+            },
+            &[],
+        )
+    }
+
+    /// Generates the DDL of this [ChangeSet] once per given schema, e.g. to
+    /// run the same migration across all tenant schemas. The returned
+    /// scripts are in the order of `schemas` and can be joined to a single
+    /// script.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.drop_table("a");
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         r#"DROP TABLE "tenant_a"."a";"#.to_string(),
+    ///         r#"DROP TABLE "tenant_b"."a";"#.to_string()
+    ///     ],
+    ///     cs.get_ddl_for_schemas(Postgres::new_rc(), &["tenant_a", "tenant_b"]).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn get_ddl_for_schemas(
+        &self,
+        dialect: Rc<dyn SqlDialect>,
+        schemas: &[&str],
+    ) -> Result<Vec<String>> {
+        schemas
+            .iter()
+            .map(|schema| self.get_ddl(dialect.for_schema(schema)))
+            .collect()
+    }
+
+    /// `indexed` are the indexed columns of the parent sets, see
+    /// [ChangeSet::foreign_key_indexes].
+    fn get_ddl_filtered(
+        &self,
+        dialect: Rc<dyn SqlDialect>,
+        filter: RenderFilter,
+        indexed: &[(String, Vec<String>)],
+    ) -> Result<String> {
+        if self.verify_extensions {
+            self.check_extensions()?;
+        }
+        let mut indexed = indexed.to_vec();
+        let fk_indexes = self.foreign_key_indexes(&mut indexed);
+
+        let render = |c: &dyn Change| -> Result<String> {
+            if let Some(tagged) = c.as_any().downcast_ref::<TaggedChanges>() {
+                return tagged.get_ddl_filtered(dialect.clone(), filter, &indexed);
+            }
+            if let Some(contextual) = c.as_any().downcast_ref::<ContextChanges>() {
+                return contextual.get_ddl_filtered(dialect.clone(), filter, &indexed);
+            }
+
+            match self.lock_timeout {
+                Some(timeout) if c.is_lock_heavy() && c.is_transactional() => {
+                    let ddl = format!(
+                        "{}\n{}",
+                        dialect.lock_timeout(timeout)?,
+                        c.get_ddl(dialect.clone())?
+                    );
+                    match dialect.reset_lock_timeout()? {
+                        reset if reset.is_empty() => Ok(ddl),
+                        reset => Ok(format!("{}\n{}", ddl, reset)),
+                    }
+                }
+                _ => c.get_ddl(dialect.clone()),
+            }
+        };
+
+        let mut statements = Vec::new();
+        for (i, c) in self.changes.iter().enumerate() {
+            statements.push(render(c.as_ref())?);
+            for (_, idx) in fk_indexes.iter().filter(|(pos, _)| *pos == i) {
+                statements.push(render(idx)?);
+            }
+        }
+
+        Ok(statements
+            .into_iter()
+            .filter(|ddl| !ddl.is_empty())
+            .collect::<Vec<String>>()
+            .join("\n\n"))
+    }
+
+    /// Fails, if a column of an extension type is added before the
+    /// extension is created.
+    fn check_extensions(&self) -> Result<()> {
+        let mut created: Vec<&str> = Vec::new();
+        for c in self.flattened_changes() {
+            let c = c.as_any();
+            if let Some(ext) = c.downcast_ref::<ExtensionChange>() {
+                created.push(&ext.name);
+            } else if let Some(t) = c.downcast_ref::<TableChange>() {
+                let mut columns = Vec::new();
+                t.apply_columns(&mut columns);
+
+                for (name, ct) in columns {
+                    match ct.required_extension() {
+                        Some(ext) if !created.contains(&ext) => {
+                            return Err(SqlPressError::invalid(format!(
+                                "Column {}.{} requires the extension {}, which is not created before",
+                                t.name, name, ext
+                            )))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Determines the indexes, which have to be created automatically for
+    /// foreign key columns, together with the position of the change they
+    /// have to follow. A foreign key is covered by an existing index (also
+    /// of a nested or parent set), if its columns lead the index in any
+    /// order. Foreign keys of nested sets are indexed when the nested set is
+    /// rendered. `indexed` starts with the indexed columns of the parent sets
+    /// and is extended by the ones of this set, including the determined
+    /// indexes.
+    fn foreign_key_indexes(
+        &self,
+        indexed: &mut Vec<(String, Vec<String>)>,
+    ) -> Vec<(usize, IndexAddCombinedChange)> {
+        if !self.auto_index_foreign_keys {
+            return Vec::new();
+        }
+
+        for c in self.flattened_changes() {
+            let c = c.as_any();
+            if let Some(t) = c.downcast_ref::<TableChange>() {
+                indexed.extend(
+                    t.indexed_columns()
+                        .into_iter()
+                        .map(|columns| (t.name.clone(), columns)),
+                );
+            } else if let Some(idx) = c.downcast_ref::<IndexAddCombinedChange>() {
+                indexed.push((idx.table_name.clone(), idx.column_names()));
+            }
+        }
+        let covered = |indexed: &[(String, Vec<String>)], table: &str, columns: &[String]| {
+            indexed.iter().any(|(t, idx_columns)| {
+                t == table
+                    && idx_columns.len() >= columns.len()
+                    && columns
+                        .iter()
+                        .all(|c| idx_columns[..columns.len()].contains(c))
+            })
+        };
+
+        let mut fk_indexes = Vec::new();
+        for (i, c) in self.changes.iter().enumerate() {
+            let Some(t) = c.as_ref().as_any().downcast_ref::<TableChange>() else {
+                continue;
+            };
+
+            for columns in t.foreign_key_columns() {
+                if covered(indexed, &t.name, &columns) {
+                    continue;
+                }
+
+                let idx_name = match &self.naming_strategy {
+                    Some(n) => n.index(&t.name, &columns),
+                    None => format!("{}_{}_idx", t.name, columns.join("_")),
+                };
+                let idx_columns = columns.iter().map(|c| c.as_str()).collect();
+                fk_indexes.push((i, index(&t.name, idx_columns).name(&idx_name).build()));
+                indexed.push((t.name.clone(), columns));
+            }
+        }
+
+        fk_indexes
+    }
+}
+
+/// Result of [ChangeSet::portability_report].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortabilityReport {
+    pub issues: Vec<PortabilityIssue>,
+}
+
+impl PortabilityReport {
+    /// Returns `true` if all changes are rendered by all dialects.
+    pub fn is_portable(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortabilityIssue {
+    /// Name of the dialect (see [SqlDialect::name])
+    pub dialect: &'static str,
+    /// Position of the change in the [ChangeSet]
+    pub change: usize,
+    pub kind: PortabilityIssueKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortabilityIssueKind {
+    /// The dialect can't render the change
+    Unsupported(SqlPressError),
+    /// The change is a raw script, which is passed to every dialect as is
+    RawScript,
+}
+
+/// Creates an extension (see [ChangeSet::create_extension]).
+#[derive(Debug)]
+pub struct ExtensionChange {
+    name: String,
+}
+
+impl Change for ExtensionChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.create_extension(&self.name)
+    }
+}
+
+/// Prerequisites for generating random UUIDs (see
+/// [ChangeSet::ensure_uuid_generation]).
+#[derive(Debug)]
+pub struct UuidGenerationChange;
+
+impl Change for UuidGenerationChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.ensure_uuid_generation()
+    }
+}
+
+/// Plain change which is run on the database without additional transformation.
+#[derive(Debug)]
+pub struct Script {
+    script: String,
+}
+
+impl Script {
+    pub fn new(script: &str) -> Self {
+        Self {
+            script: script.into(),
+        }
+    }
+}
+
+impl Change for Script {
+    fn get_ddl(&self, _dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        Ok(format!("{}\n", self.script))
+    }
+}
+
+/// Changes, which are only rendered if one of their tags is selected (see
+/// [ChangeSet::get_ddl_for_tags]).
+#[derive(Debug)]
+pub struct TaggedChanges {
+    tags: Vec<String>,
+    changes: ChangeSet,
+}
+
+impl TaggedChanges {
+    fn get_ddl_filtered(
+        &self,
+        dialect: Rc<dyn SqlDialect>,
+        filter: RenderFilter,
+        indexed: &[(String, Vec<String>)],
+    ) -> Result<String> {
+        match filter.tags {
+            Some(tags) if !self.tags.iter().any(|t| tags.contains(&t.as_str())) => Ok("".into()),
+            _ => self.changes.get_ddl_filtered(dialect, filter, indexed),
+        }
+    }
+}
+
+impl Change for TaggedChanges {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        self.changes.get_ddl(dialect)
+    }
+
+    fn is_transactional(&self) -> bool {
+        self.changes.is_transactional()
+    }
+}
+
+/// Changes, which are only rendered for the given environment contexts (see
+/// [ChangeSet::get_ddl_for_context]).
+#[derive(Debug)]
+pub struct ContextChanges {
+    contexts: Vec<String>,
+    changes: ChangeSet,
+}
+
+impl ContextChanges {
+    fn get_ddl_filtered(
+        &self,
+        dialect: Rc<dyn SqlDialect>,
+        filter: RenderFilter,
+        indexed: &[(String, Vec<String>)],
+    ) -> Result<String> {
+        match filter.context {
+            Some(context) if !self.contexts.iter().any(|c| c == context) => Ok("".into()),
+            _ => self.changes.get_ddl_filtered(dialect, filter, indexed),
+        }
+    }
+}
+
+impl Change for ContextChanges {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        self.changes.get_ddl(dialect)
+    }
+
+    fn is_transactional(&self) -> bool {
+        self.changes.is_transactional()
+    }
+}
+
+/// Selection of tagged and context-restricted changes to render. `None`
+/// renders all of them.
+#[derive(Debug, Clone, Copy, Default)]
+struct RenderFilter<'a> {
+    tags: Option<&'a [&'a str]>,
+    context: Option<&'a str>,
+}
+
+impl Default for ChangeSet {
+    fn default() -> Self {
+        Self {
+            schema: "public".into(),
+            changes: Default::default(),
+            auto_index_foreign_keys: false,
+            lock_timeout: None,
+            verify_extensions: false,
+            naming_strategy: None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "postgres"))]
+mod tests {
+    use crate::{
+        column::{
+            bigserial, citext, real, serial, text, uuid, varchar, ColumnType, DefaultConstraint,
+        },
+        expr::Expr,
+        index::ReferentialAction,
+        naming::DefaultNamingStrategy,
+        sql_dialect::postgres::Postgres,
+    };
+
+    use super::*;
+
+    #[test]
+    fn create_table() {
+        let mut cs = ChangeSet::new();
+
+        cs.create_table("xxx", |t| {
+            t.add_foreign_index("tag_id", "tag", "id", Some("fk_blubbi".into()));
+            t.add_column(
+                uuid("id")
+                    .primary(true)
+                    .default(DefaultConstraint::Plain("uuid_generate_v4()".into()))
+                    .build(),
+            );
+            t.add_column(varchar("description", None).build());
+            t.add_primary_index(vec!["id", "id2"]);
+        });
+
+        let _d = Rc::new(Postgres::new());
+        // println!("{}", cs.get_ddl(_d).unwrap());
+    }
+
+    #[test]
     fn alter_table() {
         let mut cs = ChangeSet::new();
 
-        cs.alter_table("xxx", |t| {
-            t.add_column(uuid("id2").build());
-            t.drop_column("description");
-            t.rename_column("id2", "id3");
-            t.drop_column_if_exists("id3");
-            t.add_column(varchar("description2", None).build());
-            t.alter_column("description2", ColumnType::UUID, None);
-            t.alter_column(
-                "description2",
-                ColumnType::UUID,
-                Some("%%%conversion_method%%%".into()),
+        cs.alter_table("xxx", |t| {
+            t.add_column(uuid("id2").build());
+            t.drop_column("description");
+            t.rename_column("id2", "id3");
+            t.drop_column_if_exists("id3");
+            t.add_column(varchar("description2", None).build());
+            t.alter_column("description2", ColumnType::UUID, None);
+            t.alter_column(
+                "description2",
+                ColumnType::UUID,
+                Some("%%%conversion_method%%%".into()),
+            );
+        });
+
+        cs.run_script("CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\";");
+
+        let _d = Postgres::new_rc();
+        // println!("{}", cs.get_ddl(_d).unwrap());
+    }
+
+    #[test]
+    fn alter_table_rename_column() {
+        let mut cs = ChangeSet::new();
+        cs.alter_table("tag", |t| {
+            t.add_column(text("color").build());
+            t.rename_column("name", "title");
+            t.drop_column("slug");
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"ALTER TABLE "public"."tag"
+ADD COLUMN "color" text;
+
+ALTER TABLE "public"."tag"
+RENAME COLUMN "name" TO "title";
+
+ALTER TABLE "public"."tag"
+DROP COLUMN "slug";"#
+        );
+    }
+
+    #[test]
+    fn create_index_concurrently() {
+        let mut cs = ChangeSet::new();
+        cs.lock_timeout(Duration::from_secs(5));
+        cs.create_index(
+            index("users", vec!["email"])
+                .name("idx_users_email")
+                .concurrently(true)
+                .build(),
+        );
+
+        assert!(!cs.is_transactional());
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE INDEX CONCURRENTLY "idx_users_email" ON "public"."users" ("email");"#
+        );
+
+        let mut cs = ChangeSet::new();
+        cs.alter_table("users", |t| {
+            t.drop_index_with("idx_users_email", false, true);
+        });
+        assert!(!cs.is_transactional());
+
+        let mut cs = ChangeSet::new();
+        cs.alter_table("users", |t| {
+            t.drop_index("idx_users_email");
+        });
+        assert!(cs.is_transactional());
+    }
+
+    #[test]
+    fn alter_table_drop_index() {
+        let mut cs = ChangeSet::new();
+
+        cs.alter_table("users", |t| {
+            t.drop_unique_constraint("uq_users_email");
+            t.drop_index("idx_users_email");
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"ALTER TABLE "public"."users"
+DROP CONSTRAINT "uq_users_email";
+
+DROP INDEX "public"."idx_users_email";"#
+        );
+
+        let mut cs = ChangeSet::new();
+
+        cs.alter_table("users", |t| {
+            t.drop_index("idx_users_email");
+            t.drop_index_with("idx_users_name", true, true);
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"DROP INDEX "public"."idx_users_email";
+
+DROP INDEX CONCURRENTLY IF EXISTS "public"."idx_users_name";"#
+        );
+    }
+
+    #[test]
+    fn create_table_order() {
+        let mut cs = ChangeSet::new();
+
+        cs.create_table("order", |t| {
+            t.add_primary_index(vec!["id"]);
+            t.add_foreign_index("user_id", "user", "id", None);
+            t.add_column(uuid("id").build());
+            t.add_column(uuid("user_id").build());
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."order" (
+"id" uuid,
+"user_id" uuid,
+PRIMARY KEY("id"),
+FOREIGN KEY("user_id") REFERENCES "public"."user"("id")
+);"#
+        );
+    }
+
+    #[test]
+    fn column_references() {
+        let mut cs = ChangeSet::new();
+
+        cs.create_table("order", |t| {
+            t.add_column(uuid("id").primary(true).build());
+            t.add_column(
+                uuid("user_id")
+                    .not_null(true)
+                    .references("user", "id")
+                    .on_delete(ReferentialAction::Cascade)
+                    .build(),
+            );
+        });
+        cs.alter_table("order", |t| {
+            t.add_column(
+                uuid("coupon_id")
+                    .references("coupon", "id")
+                    .name("fk_order_coupon")
+                    .on_delete(ReferentialAction::SetNull)
+                    .on_update(ReferentialAction::Restrict)
+                    .build(),
             );
         });
 
-        cs.run_script("CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\";");
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."order" (
+"id" uuid PRIMARY KEY,
+"user_id" uuid NOT NULL,
+FOREIGN KEY("user_id") REFERENCES "public"."user"("id") ON DELETE CASCADE
+);
 
-        let _d = Postgres::new_rc();
-        // println!("{}", cs.get_ddl(_d));
+ALTER TABLE "public"."order"
+ADD COLUMN "coupon_id" uuid,
+ADD CONSTRAINT "fk_order_coupon" FOREIGN KEY("coupon_id") REFERENCES "public"."coupon"("id") ON DELETE SET NULL ON UPDATE RESTRICT;"#
+        );
+    }
+
+    #[test]
+    fn composite_foreign_key() {
+        let mut cs = ChangeSet::new();
+        cs.auto_index_foreign_keys(true);
+
+        cs.create_table("order", |t| {
+            t.add_column(uuid("id").primary(true).build());
+            t.add_column(uuid("tenant_id").not_null(true).build());
+            t.add_column(uuid("user_id").not_null(true).build());
+            t.add_foreign_index_composite(
+                &["tenant_id", "user_id"],
+                "user",
+                &["tenant_id", "id"],
+                Some("fk_order_user".into()),
+            );
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."order" (
+"id" uuid PRIMARY KEY,
+"tenant_id" uuid NOT NULL,
+"user_id" uuid NOT NULL,
+CONSTRAINT "fk_order_user" FOREIGN KEY("tenant_id", "user_id") REFERENCES "public"."user"("tenant_id", "id")
+);
+
+CREATE INDEX "order_tenant_id_user_id_idx" ON "public"."order" ("tenant_id", "user_id");"#
+        );
+    }
+
+    #[test]
+    fn table_index() {
+        let mut cs = ChangeSet::new();
+        cs.auto_index_foreign_keys(true);
+
+        cs.alter_table("order", |t| {
+            t.add_column(uuid("user_id").references("user", "id").build());
+            t.add_index(&["user_id", "created_at"], None);
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"ALTER TABLE "public"."order"
+ADD COLUMN "user_id" uuid,
+ADD FOREIGN KEY("user_id") REFERENCES "public"."user"("id");
+
+CREATE INDEX ON "public"."order" ("user_id", "created_at");"#
+        );
+    }
+
+    #[test]
+    fn naming_strategy() {
+        let mut cs = ChangeSet::new();
+        cs.naming_strategy(Rc::new(DefaultNamingStrategy::new()));
+        cs.auto_index_foreign_keys(true);
+
+        cs.create_table("order", |t| {
+            t.add_column(uuid("id").primary(true).build());
+            t.add_column(uuid("user_id").references("user", "id").build());
+        });
+        cs.create_index(crate::index::index("order", vec!["id", "user_id"]).build());
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."order" (
+"id" uuid PRIMARY KEY,
+"user_id" uuid,
+CONSTRAINT "fk_order_user_id" FOREIGN KEY("user_id") REFERENCES "public"."user"("id")
+);
+
+CREATE INDEX "idx_order_user_id" ON "public"."order" ("user_id");
+
+CREATE INDEX "idx_order_id_user_id" ON "public"."order" ("id", "user_id");"#
+        );
+    }
+
+    #[test]
+    fn create_table_column_order() {
+        let mut cs = ChangeSet::new();
+
+        cs.create_table("order", |t| {
+            t.column_order(&["id", "user_id"]);
+            t.add_column(text("note").build());
+            t.add_column(uuid("user_id").build());
+            t.add_column(uuid("id").build());
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."order" (
+"id" uuid,
+"user_id" uuid,
+"note" text
+);"#
+        );
+    }
+
+    #[test]
+    fn enum_column() {
+        let mut cs = ChangeSet::new();
+        cs.create_table("orders", |t| {
+            t.add_column(uuid("id").primary(true).build());
+            t.add_enum_column("status", &["new", "paid", "void"]);
+        });
+        cs.alter_table("orders", |t| t.add_enum_column("kind", &["web"]));
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TYPE "public"."orders_status" AS ENUM ('new', 'paid', 'void');
+
+CREATE TABLE "public"."orders" (
+"id" uuid PRIMARY KEY,
+"status" "public"."orders_status"
+);
+
+CREATE TYPE "public"."orders_kind" AS ENUM ('web');
+
+ALTER TABLE "public"."orders"
+ADD COLUMN "kind" "public"."orders_kind";"#
+        );
+    }
+
+    #[test]
+    fn version_column() {
+        let mut cs = ChangeSet::new();
+        cs.create_table("orders", |t| {
+            t.add_column(uuid("id").primary(true).build());
+            t.version_column(true);
+        });
+        cs.alter_table("customer", |t| t.version_column(false));
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."orders" (
+"id" uuid PRIMARY KEY,
+"version" integer NOT NULL DEFAULT 0
+);
+
+CREATE FUNCTION "public"."orders_version_trigger"() RETURNS trigger AS $sql_press$
+BEGIN
+NEW."version" := OLD."version" + 1;
+RETURN NEW;
+END;
+$sql_press$ LANGUAGE plpgsql;
+
+CREATE TRIGGER "orders_version_trigger" BEFORE UPDATE ON "public"."orders"
+FOR EACH ROW EXECUTE FUNCTION "public"."orders_version_trigger"();
+
+ALTER TABLE "public"."customer"
+ADD COLUMN "version" integer NOT NULL DEFAULT 0;"#
+        );
+    }
+
+    #[test]
+    fn add_audit_history() {
+        let mut cs = ChangeSet::new();
+        cs.create_table("orders", |t| {
+            t.add_column(uuid("id").primary(true).build());
+            t.add_column(text("note").build());
+        });
+        cs.alter_table("orders", |t| {
+            t.rename_column("note", "comment");
+            t.add_column(uuid("customer_id").build());
+        });
+        cs.add_audit_history("orders");
+
+        assert_eq!(
+            cs.changes[2].get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."orders_history" (
+"id" uuid,
+"comment" text,
+"customer_id" uuid,
+"operation" VARCHAR(6) NOT NULL,
+"changed_at" timestamp with time zone NOT NULL DEFAULT now(),
+"changed_by" VARCHAR(128) NOT NULL DEFAULT current_user
+);
+
+CREATE FUNCTION "public"."orders_history_trigger"() RETURNS trigger AS $sql_press$
+BEGIN
+IF TG_OP = 'DELETE' THEN
+INSERT INTO "public"."orders_history" ("id", "comment", "customer_id", "operation") VALUES (OLD."id", OLD."comment", OLD."customer_id", TG_OP);
+RETURN OLD;
+END IF;
+INSERT INTO "public"."orders_history" ("id", "comment", "customer_id", "operation") VALUES (NEW."id", NEW."comment", NEW."customer_id", TG_OP);
+RETURN NEW;
+END;
+$sql_press$ LANGUAGE plpgsql;
+
+CREATE TRIGGER "orders_history_trigger" AFTER INSERT OR UPDATE OR DELETE ON "public"."orders"
+FOR EACH ROW EXECUTE FUNCTION "public"."orders_history_trigger"();"#
+        );
+    }
+
+    #[test]
+    fn add_audit_history_serial() {
+        let mut cs = ChangeSet::new();
+        cs.create_table("orders", |t| {
+            t.add_column(serial("id").primary(true).build());
+            t.add_column(bigserial("number").build());
+        });
+        cs.add_audit_history("orders");
+
+        let ddl = cs.changes[1].get_ddl(Postgres::new_rc()).unwrap();
+        assert!(ddl.contains(
+            r#""id" integer,
+"number" bigint,"#
+        ));
+    }
+
+    #[test]
+    fn add_audit_history_unknown_table() {
+        let mut cs = ChangeSet::new();
+        cs.add_audit_history("orders");
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()),
+            Err(SqlPressError::invalid(
+                "the audit history of orders requires the table to be created in the same ChangeSet"
+            ))
+        );
+    }
+
+    #[test]
+    fn add_audit_history_nested_table() {
+        let mut cs = ChangeSet::new();
+        cs.only_if(Precondition::TableMissing("orders".into()), |cs| {
+            cs.tagged(&["billing"], |cs| {
+                cs.create_table("orders", |t| {
+                    t.add_column(uuid("id").primary(true).build());
+                });
+            });
+        });
+        cs.alter_table("orders", |t| t.add_column(text("note").build()));
+        cs.add_audit_history("orders");
+
+        let ddl = cs.changes[2].get_ddl(Postgres::new_rc()).unwrap();
+        assert!(ddl.contains(
+            r#""id" uuid,
+"note" text,"#
+        ));
+    }
+
+    #[test]
+    fn check_constraint() {
+        let mut cs = ChangeSet::new();
+        cs.create_table("product", |t| {
+            t.add_column(real("price").build());
+            t.add_column(real("discount").build());
+            t.add_check_constraint(
+                "chk_discount",
+                Expr::col("discount").lt_eq(Expr::col("price")),
+            );
+        });
+        cs.alter_table("product", |t| {
+            t.drop_check_constraint("chk_discount");
+            t.add_check_constraint("chk_price_positive", Expr::col("price").gt(Expr::val(0)));
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."product" (
+"price" real,
+"discount" real,
+CONSTRAINT "chk_discount" CHECK ("discount" <= "price")
+);
+
+ALTER TABLE "public"."product"
+DROP CONSTRAINT "chk_discount",
+ADD CONSTRAINT "chk_price_positive" CHECK ("price" > 0);"#
+        );
+    }
+
+    #[test]
+    fn check_constraint_not_valid() {
+        let mut cs = ChangeSet::new();
+        cs.alter_table("product", |t| {
+            t.add_check_constraint_not_valid(
+                "chk_price_positive",
+                Expr::col("price").gt(Expr::val(0)),
+            );
+        });
+        cs.alter_table("product", |t| t.validate_constraint("chk_price_positive"));
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"ALTER TABLE "public"."product"
+ADD CONSTRAINT "chk_price_positive" CHECK ("price" > 0) NOT VALID;
+
+ALTER TABLE "public"."product"
+VALIDATE CONSTRAINT "chk_price_positive";"#
+        );
+    }
+
+    #[test]
+    fn verify_extensions() {
+        let mut cs = ChangeSet::new();
+        cs.verify_extensions(true);
+        cs.alter_table("user", |t| t.add_column(citext("email").build()));
+        cs.create_extension("citext");
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()),
+            Err(SqlPressError::invalid(
+                "Column user.email requires the extension citext, which is not created before"
+            ))
+        );
+    }
+
+    #[test]
+    fn verify_extensions_nested() {
+        let mut cs = ChangeSet::new();
+        cs.verify_extensions(true);
+        cs.create_extension("citext");
+        cs.tagged(&["crm"], |cs| {
+            cs.alter_table("user", |t| t.add_column(citext("email").build()));
+        });
+        assert!(cs.get_ddl(Postgres::new_rc()).is_ok());
+
+        let mut cs = ChangeSet::new();
+        cs.verify_extensions(true);
+        cs.tagged(&["crm"], |cs| {
+            cs.alter_table("user", |t| t.add_column(citext("email").build()));
+        });
+        assert!(cs.get_ddl(Postgres::new_rc()).is_err());
+    }
+
+    #[cfg(feature = "mssql")]
+    #[test]
+    fn portability_report() {
+        use crate::sql_dialect::Mssql;
+
+        let mut cs = ChangeSet::new();
+        cs.drop_table("a");
+        cs.create_composite_type("address", vec![("street", ColumnType::TEXT)]);
+        cs.run_script("VACUUM;");
+
+        let report = cs.portability_report(&[Postgres::new_rc(), Mssql::new_rc()]);
+        assert_eq!(
+            report
+                .issues
+                .iter()
+                .map(|i| (i.dialect, i.change))
+                .collect::<Vec<(&str, usize)>>(),
+            vec![("postgres", 2), ("mssql", 1), ("mssql", 2)]
+        );
+        assert!(matches!(
+            report.issues[1].kind,
+            PortabilityIssueKind::Unsupported(SqlPressError::UnsupportedByDialect(_))
+        ));
+        assert!(cs.get_ddl(Mssql::new_rc()).is_err());
+    }
+
+    #[test]
+    fn portability_report_nested_script() {
+        let mut cs = ChangeSet::new();
+        cs.drop_table("a");
+        cs.tagged(&["x"], |cs| cs.run_script("SELECT 1;"));
+        cs.only_if(Precondition::TableExists("a".into()), |cs| {
+            cs.contexts(&["dev"], |cs| cs.run_script("SELECT 2;"))
+        });
+
+        let report = cs.portability_report(&[Postgres::new_rc()]);
+        assert_eq!(
+            report
+                .issues
+                .iter()
+                .map(|i| (i.change, i.kind.clone()))
+                .collect::<Vec<(usize, PortabilityIssueKind)>>(),
+            vec![
+                (1, PortabilityIssueKind::RawScript),
+                (2, PortabilityIssueKind::RawScript)
+            ]
+        );
+    }
+
+    #[test]
+    fn replace_primary_key() {
+        let mut cs = ChangeSet::new();
+
+        cs.alter_table("tag_user", |t| {
+            t.add_primary_index(vec!["tag_id", "user_id"]);
+            t.drop_column("id");
+            t.drop_primary_key(None);
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"ALTER TABLE "public"."tag_user"
+DROP CONSTRAINT "tag_user_pkey",
+DROP COLUMN "id",
+ADD PRIMARY KEY("tag_id", "user_id");"#
+        );
+    }
+
+    #[test]
+    fn drop_named_primary_key() {
+        let mut cs = ChangeSet::new();
+
+        cs.alter_table("orders", |t| {
+            t.add_primary_index_named("pk_orders", vec!["tenant_id", "id"]);
+        });
+        cs.alter_table("orders", |t| {
+            t.drop_primary_key(Some("pk_orders"));
+        });
+
+        assert!(cs
+            .get_ddl(Postgres::new_rc())
+            .unwrap()
+            .ends_with("ALTER TABLE \"public\".\"orders\"\nDROP CONSTRAINT \"pk_orders\";"));
+    }
+
+    #[test]
+    fn alter_table_order() {
+        let mut cs = ChangeSet::new();
+
+        cs.alter_table("order", |t| {
+            t.add_foreign_index("customer_id", "customer", "id", None);
+            t.drop_column("user_id");
+            t.add_column(uuid("customer_id").build());
+            t.drop_unique_constraint("uq_order_user_id_number");
+        });
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"ALTER TABLE "public"."order"
+DROP CONSTRAINT "uq_order_user_id_number",
+DROP COLUMN "user_id",
+ADD COLUMN "customer_id" uuid,
+ADD FOREIGN KEY("customer_id") REFERENCES "public"."customer"("id");"#
+        );
+    }
+
+    #[test]
+    fn auto_index_foreign_keys() {
+        let mut cs = ChangeSet::new();
+        cs.auto_index_foreign_keys(true);
+
+        cs.create_table("order", |t| {
+            t.add_column(uuid("id").build());
+            t.add_column(uuid("user_id").build());
+            t.add_column(uuid("tenant_id").build());
+            t.add_column(uuid("product_id").build());
+            t.add_primary_index(vec!["tenant_id", "id"]);
+            t.add_foreign_index("user_id", "user", "id", None);
+            t.add_foreign_index("tenant_id", "tenant", "id", None);
+            t.add_foreign_index("product_id", "product", "id", None);
+        });
+        cs.create_index(crate::index::index("order", vec!["product_id"]).build());
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."order" (
+"id" uuid,
+"user_id" uuid,
+"tenant_id" uuid,
+"product_id" uuid,
+PRIMARY KEY("tenant_id", "id"),
+FOREIGN KEY("user_id") REFERENCES "public"."user"("id"),
+FOREIGN KEY("tenant_id") REFERENCES "public"."tenant"("id"),
+FOREIGN KEY("product_id") REFERENCES "public"."product"("id")
+);
+
+CREATE INDEX "order_user_id_idx" ON "public"."order" ("user_id");
+
+CREATE INDEX ON "public"."order" ("product_id");"#
+        );
+    }
+
+    #[test]
+    fn auto_index_composite_foreign_keys() {
+        let mut cs = ChangeSet::new();
+        cs.auto_index_foreign_keys(true);
+
+        cs.create_table("order_item", |t| {
+            t.add_column(uuid("id").build());
+            t.add_column(uuid("tenant_id").build());
+            t.add_column(uuid("order_id").build());
+            t.add_column(uuid("product_id").build());
+            t.add_primary_index(vec!["order_id", "tenant_id", "id"]);
+            t.add_index(&["tenant_id"], None);
+            t.add_foreign_index_composite(
+                &["tenant_id", "order_id"],
+                "order",
+                &["tenant_id", "id"],
+                None,
+            );
+            t.add_foreign_index_composite(
+                &["tenant_id", "product_id"],
+                "product",
+                &["tenant_id", "id"],
+                None,
+            );
+        });
+
+        let ddl = cs.get_ddl(Postgres::new_rc()).unwrap();
+        assert!(!ddl.contains("order_item_tenant_id_order_id_idx"));
+        assert!(ddl.ends_with(
+            r#"CREATE INDEX "order_item_tenant_id_product_id_idx" ON "public"."order_item" ("tenant_id", "product_id");"#
+        ));
+    }
+
+    #[test]
+    fn auto_index_foreign_keys_nested() {
+        let mut cs = ChangeSet::new();
+        cs.auto_index_foreign_keys(true);
+
+        cs.create_table("order", |t| {
+            t.add_column(uuid("user_id").build());
+            t.add_foreign_index("user_id", "user", "id", None);
+        });
+        cs.tagged(&["reporting"], |cs| {
+            cs.create_index(crate::index::index("order", vec!["user_id"]).build());
+        });
+
+        assert!(!cs
+            .get_ddl(Postgres::new_rc())
+            .unwrap()
+            .contains("order_user_id_idx"));
+    }
+
+    #[test]
+    fn auto_index_foreign_keys_nested_parent_index() {
+        let mut cs = ChangeSet::new();
+        cs.auto_index_foreign_keys(true);
+
+        cs.create_table("orders", |t| {
+            t.add_column(uuid("user_id").build());
+        });
+        cs.alter_table("orders", |t| {
+            t.add_index(&["user_id"], Some("orders_user_idx"));
+        });
+        cs.tagged(&["billing"], |cs| {
+            cs.alter_table("orders", |t| {
+                t.add_foreign_index("user_id", "user", "id", None);
+            });
+        });
+
+        for ddl in [
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            cs.get_ddl_for_tags(Postgres::new_rc(), &["billing"])
+                .unwrap(),
+        ] {
+            assert!(ddl.contains("orders_user_idx"));
+            assert!(!ddl.contains("orders_user_id_idx"));
+        }
+    }
+
+    #[test]
+    fn lock_timeout() {
+        let mut cs = ChangeSet::new();
+        cs.lock_timeout(Duration::from_secs(3));
+
+        cs.create_table("tag", |t| {
+            t.add_column(uuid("id").build());
+        });
+        cs.alter_table("tag", |t| {
+            t.add_column(varchar("name", None).build());
+        });
+        cs.reindex_index("tag_pkey", true);
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            r#"CREATE TABLE "public"."tag" (
+"id" uuid
+);
+
+SET LOCAL lock_timeout = '3000ms';
+ALTER TABLE "public"."tag"
+ADD COLUMN "name" VARCHAR;
+
+REINDEX INDEX CONCURRENTLY "public"."tag_pkey";"#
+        );
+    }
+
+    #[cfg(feature = "mssql")]
+    #[test]
+    fn lock_timeout_mssql() {
+        let mut cs = ChangeSet::new();
+        cs.lock_timeout(Duration::from_secs(3));
+        cs.drop_table("tag");
+        cs.run_script("SELECT 1;");
+
+        let ddl = cs.get_ddl(crate::sql_dialect::Mssql::new_rc()).unwrap();
+        assert!(ddl.starts_with(
+            "SET LOCK_TIMEOUT 3000;\nDROP TABLE [dbo].[tag];\nSET LOCK_TIMEOUT -1;\n\nSELECT 1;"
+        ));
+    }
+
+    #[test]
+    fn lock_timeout_non_transactional() {
+        #[derive(Debug)]
+        struct NonTransactionalChange;
+
+        impl Change for NonTransactionalChange {
+            fn get_ddl(&self, _dialect: Rc<dyn SqlDialect>) -> Result<String> {
+                Ok("VACUUM FULL \"tag\";".into())
+            }
+
+            fn is_lock_heavy(&self) -> bool {
+                true
+            }
+
+            fn is_transactional(&self) -> bool {
+                false
+            }
+        }
+
+        let mut cs = ChangeSet::new();
+        cs.lock_timeout(Duration::from_secs(3));
+        cs.changes.push(Box::new(NonTransactionalChange));
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            "VACUUM FULL \"tag\";"
+        );
+    }
+
+    #[test]
+    fn tagged() {
+        let mut cs = ChangeSet::new();
+        cs.drop_table("a");
+        cs.tagged(&["search", "fts"], |cs| {
+            cs.drop_table("b");
+            cs.tagged(&["billing"], |cs| cs.drop_table("c"));
+        });
+        cs.tagged(&["billing"], |cs| cs.drop_table("d"));
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            "DROP TABLE \"public\".\"a\";\n\nDROP TABLE \"public\".\"b\";\n\nDROP TABLE \"public\".\"c\";\n\nDROP TABLE \"public\".\"d\";"
+        );
+        assert_eq!(
+            cs.get_ddl_for_tags(Postgres::new_rc(), &["fts"]).unwrap(),
+            "DROP TABLE \"public\".\"a\";\n\nDROP TABLE \"public\".\"b\";"
+        );
+        assert_eq!(
+            cs.get_ddl_for_tags(Postgres::new_rc(), &["billing"])
+                .unwrap(),
+            "DROP TABLE \"public\".\"a\";\n\nDROP TABLE \"public\".\"d\";"
+        );
+        assert_eq!(
+            cs.get_ddl_for_tags(Postgres::new_rc(), &[]).unwrap(),
+            "DROP TABLE \"public\".\"a\";"
+        );
+    }
+
+    #[test]
+    fn contexts() {
+        let mut cs = ChangeSet::new();
+        cs.drop_table("a");
+        cs.contexts(&["dev", "staging"], |cs| cs.drop_table("b"));
+        cs.contexts(&["prod"], |cs| cs.drop_table("c"));
+
+        assert_eq!(
+            cs.get_ddl(Postgres::new_rc()).unwrap(),
+            "DROP TABLE \"public\".\"a\";\n\nDROP TABLE \"public\".\"b\";\n\nDROP TABLE \"public\".\"c\";"
+        );
+        assert_eq!(
+            cs.get_ddl_for_context(Postgres::new_rc(), "staging")
+                .unwrap(),
+            "DROP TABLE \"public\".\"a\";\n\nDROP TABLE \"public\".\"b\";"
+        );
+        assert_eq!(
+            cs.get_ddl_for_context(Postgres::new_rc(), "test").unwrap(),
+            "DROP TABLE \"public\".\"a\";"
+        );
+    }
+
+    #[test]
+    fn only_if_with_version_column() {
+        let mut cs = ChangeSet::new();
+        cs.only_if(Precondition::TableMissing("orders".into()), |cs| {
+            cs.create_table("orders", |t| {
+                t.add_column(uuid("id").primary(true).build());
+                t.version_column(true);
+            });
+        });
+
+        let ddl = cs.get_ddl(Postgres::new_rc()).unwrap();
+        assert!(ddl.starts_with("DO $sql_press_1$\n"));
+        assert!(ddl.ends_with("\n$sql_press_1$;"));
+    }
+
+    #[test]
+    fn only_if_rejects_non_transactional_changes() {
+        let mut cs = ChangeSet::new();
+        cs.only_if(Precondition::TableExists("tags".into()), |cs| {
+            cs.drop_index_with("tags_name_idx", true, true);
+        });
+
+        assert!(matches!(
+            cs.get_ddl(Postgres::new_rc()),
+            Err(SqlPressError::InvalidChange(_))
+        ));
     }
 
     #[test]
@@ -297,7 +2707,7 @@ mod tests {
         cs.rename_table("tags", "tag");
 
         let _d = Rc::new(Postgres::new());
-        // println!("{}", cs.get_ddl(d));
+        // println!("{}", cs.get_ddl(d).unwrap());
     }
 
     #[test]
@@ -307,6 +2717,6 @@ mod tests {
         cs.drop_table("tag");
 
         let _d = Rc::new(Postgres::new());
-        // println!("{}", cs.get_ddl(d));
+        // println!("{}", cs.get_ddl(d).unwrap());
     }
 }
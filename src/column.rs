@@ -4,9 +4,12 @@ use std::rc::Rc;
 
 use crate::{
     change::Change,
-    index::{IndexAdd, IndexAlter},
+    error::{Result, SqlPressError},
+    expr::{Expr, Value},
+    index::{IndexAdd, IndexAddForeignChange, IndexAlter, ReferentialAction},
     sql_dialect::SqlDialect,
     table::Table,
+    types::{TypeChange, TypeChangeOp},
 };
 
 #[derive(Debug, Clone)]
@@ -20,13 +23,38 @@ pub struct Constraints {
     pub(crate) primary: bool,
     pub(crate) not_null: bool,
     pub(crate) unique: bool,
+    /// Name of the inline unique constraint
+    pub(crate) unique_name: Option<String>,
+    /// Treats `NULL` values as equal in the inline unique constraint
+    pub(crate) nulls_not_distinct: bool,
+    pub(crate) identity: bool,
     pub(crate) default: DefaultConstraint,
+    /// Condition of an inline `CHECK` constraint
+    pub(crate) check: Option<Expr>,
+    /// Expression of a stored generated column
+    pub(crate) generated: Option<Expr>,
 }
 
 #[derive(Debug, Clone)]
 pub enum DefaultConstraint {
     None,
     Plain(String),
+    /// Dialect independent default expression
+    Expr(Expr),
+    /// Random UUID, generated with the dialect specific function (see
+    /// [ChangeSet::ensure_uuid_generation][crate::change::ChangeSet::ensure_uuid_generation])
+    RandomUuid,
+    /// Time of the current transaction, rendered with the dialect specific
+    /// function (e.g. `now()` on postgres)
+    CurrentTimestamp,
+}
+
+impl DefaultConstraint {
+    /// Literal default value, which is escaped by the dialect, e.g.
+    /// `DefaultConstraint::value("it's")` or `DefaultConstraint::value(true)`.
+    pub fn value<V: Into<Value>>(value: V) -> Self {
+        DefaultConstraint::Expr(Expr::val(value))
+    }
 }
 
 impl Constraints {
@@ -43,20 +71,26 @@ impl Default for Constraints {
             primary: false,
             not_null: false,
             unique: false,
+            unique_name: None,
+            nulls_not_distinct: false,
+            identity: false,
             default: DefaultConstraint::None,
+            check: None,
+            generated: None,
         }
     }
 }
 
 #[derive(Debug)]
 pub struct ColumnRenameChange {
+    pub(crate) table_name: String,
     pub(crate) name: String,
     pub(crate) new_name: String,
 }
 
 impl Change for ColumnRenameChange {
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
-        dialect.rename_column(&self.name, &self.new_name)
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.rename_column(&self.table_name, &self.name, &self.new_name, false)
     }
 }
 
@@ -68,11 +102,45 @@ pub struct ColumnAlterChange {
 }
 
 impl Change for ColumnAlterChange {
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
         dialect.alter_column(&self.name, &self.ct, self.conversion_method.as_deref())
     }
 }
 
+/// Storage strategy of a column, which defines whether its values are
+/// compressed and/or stored out of line (postgres `SET STORAGE`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Storage {
+    Plain,
+    External,
+    Extended,
+    Main,
+}
+
+#[derive(Debug)]
+pub struct ColumnStorageChange {
+    pub(crate) name: String,
+    pub(crate) storage: Storage,
+}
+
+impl Change for ColumnStorageChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.set_column_storage(&self.name, &self.storage)
+    }
+}
+
+#[derive(Debug)]
+pub struct ColumnCompressionChange {
+    pub(crate) name: String,
+    pub(crate) compression: String,
+}
+
+impl Change for ColumnCompressionChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.set_column_compression(&self.name, &self.compression)
+    }
+}
+
 #[derive(Debug)]
 pub struct ColumnDropChange {
     pub(crate) name: String,
@@ -80,7 +148,7 @@ pub struct ColumnDropChange {
 }
 
 impl Change for ColumnDropChange {
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
         dialect.drop_column(&self.name, self.if_exists)
     }
 }
@@ -90,7 +158,10 @@ pub struct ColumnAddChange {
     pub(crate) name: String,
     pub(crate) ct: ColumnType,
     pub(crate) with_prefix: bool,
-    constraints: Constraints,
+    pub(crate) constraints: Constraints,
+    /// Foreign key of the column (see [ColumnAddBuilder::references]), which
+    /// is added to the table together with the column
+    pub(crate) foreign_key: Option<IndexAddForeignChange>,
 }
 
 impl ColumnAddChange {
@@ -100,12 +171,46 @@ impl ColumnAddChange {
             ct,
             with_prefix: false,
             constraints: Constraints::new(),
+            foreign_key: None,
+        }
+    }
+
+    /// Rejects contradictory or redundant constraints (see
+    /// [ColumnAddBuilder::try_build]).
+    fn validate(self) -> Result<ColumnAddChange, SqlPressError> {
+        let c = &self.constraints;
+        let null_default = match &c.default {
+            DefaultConstraint::Expr(Expr::Value(Value::Null)) => true,
+            DefaultConstraint::Plain(s) => s.trim().eq_ignore_ascii_case("null"),
+            _ => false,
+        };
+
+        let problem = if c.primary && c.unique {
+            Some("is the primary key and therefore already unique")
+        } else if (c.primary || c.not_null) && null_default {
+            Some("is not nullable, but defaults to NULL")
+        } else if c.identity && !matches!(c.default, DefaultConstraint::None) {
+            Some("is an identity column, which can't have a default")
+        } else if c.generated.is_some() && !matches!(c.default, DefaultConstraint::None) {
+            Some("is a generated column, which can't have a default")
+        } else if c.generated.is_some() && c.identity {
+            Some("is a generated column, which can't be an identity column")
+        } else {
+            None
+        };
+
+        match problem {
+            Some(p) => Err(SqlPressError::InvalidChange(format!(
+                "Column {} {}",
+                self.name, p
+            ))),
+            None => Ok(self),
         }
     }
 }
 
 impl Change for ColumnAddChange {
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
         dialect.add_column(&self.name, self.with_prefix, &self.ct, &self.constraints)
     }
 }
@@ -139,15 +244,188 @@ impl ColumnAddBuilder {
         self
     }
 
+    /// Makes the column unique and treats `NULL` values as equal
+    /// (`UNIQUE NULLS NOT DISTINCT`), so only a single row may be `NULL`.
+    pub fn unique_nulls_not_distinct(mut self, nulls_not_distinct: bool) -> Self {
+        self.inner.constraints.unique = self.inner.constraints.unique || nulls_not_distinct;
+        self.inner.constraints.nulls_not_distinct = nulls_not_distinct;
+
+        self
+    }
+
+    /// Marks the column as an auto-incrementing identity column.
+    pub fn identity(mut self, identity: bool) -> Self {
+        self.inner.constraints.identity = identity;
+
+        self
+    }
+
     pub fn default(mut self, default: DefaultConstraint) -> Self {
         self.inner.constraints.default = default;
 
         self
     }
 
+    /// Adds an inline `CHECK` constraint with the given condition, e.g.
+    /// `Expr::col("price").gt(Expr::val(0))`.
+    pub fn check(mut self, condition: Expr) -> Self {
+        self.inner.constraints.check = Some(condition);
+
+        self
+    }
+
+    /// Makes the column a stored generated column, which is computed from
+    /// `expression` (`GENERATED ALWAYS AS (..) STORED` on postgres,
+    /// `AS (..) PERSISTED` on T-SQL).
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::{change::ChangeSet, column::text, expr::Expr};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.alter_table("users", |t| {
+    ///     t.add_column(
+    ///         text("email_lower")
+    ///             .generated(Expr::func("lower", vec![Expr::col("email")]))
+    ///             .build(),
+    ///     );
+    /// });
+    /// ```
+    pub fn generated(mut self, expression: Expr) -> Self {
+        self.inner.constraints.generated = Some(expression);
+
+        self
+    }
+
+    /// Makes the column a stored generated [tsvector] column, which is
+    /// computed from `document` with the text search configuration `config`
+    /// (e.g. `english`). The configuration has to exist when the column is
+    /// added, it is not validated.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, column::tsvector, expr::Expr, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.alter_table("posts", |t| {
+    ///     t.add_column(
+    ///         tsvector("body_search")
+    ///             .text_search(
+    ///                 "english",
+    ///                 Expr::func("coalesce", vec![Expr::col("body"), Expr::val("")]),
+    ///             )
+    ///             .build(),
+    ///     );
+    /// });
+    ///
+    /// assert_eq!(r#"ALTER TABLE "public"."posts"
+    /// ADD COLUMN "body_search" tsvector GENERATED ALWAYS AS (to_tsvector('english', coalesce("body", ''))) STORED;"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn text_search(self, config: &str, document: Expr) -> Self {
+        self.generated(Expr::func("to_tsvector", vec![Expr::val(config), document]))
+    }
+
+    /// Adds a foreign key from this column to `foreign_column_name` of
+    /// `foreign_table_name`, which is added together with the column.
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::{change::ChangeSet, column::uuid, index::ReferentialAction};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.alter_table("order", |t| {
+    ///     t.add_column(
+    ///         uuid("user_id")
+    ///             .not_null(true)
+    ///             .references("user", "id")
+    ///             .on_delete(ReferentialAction::Cascade)
+    ///             .build(),
+    ///     );
+    /// });
+    /// ```
+    pub fn references(
+        mut self,
+        foreign_table_name: &str,
+        foreign_column_name: &str,
+    ) -> ColumnReferencesBuilder {
+        self.inner.foreign_key = Some(IndexAddForeignChange::new(
+            &self.inner.name,
+            foreign_table_name,
+            foreign_column_name,
+        ));
+
+        ColumnReferencesBuilder { inner: self.inner }
+    }
+
     pub fn build(self) -> ColumnAddChange {
         self.inner
     }
+
+    /// Same as [ColumnAddBuilder::build], but rejects contradictory or
+    /// redundant constraints, e.g. a primary key, which is also marked as
+    /// unique, or a `NOT NULL` column, which defaults to `NULL`.
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::column::integer;
+    ///
+    /// assert!(integer("id").primary(true).unique(true).try_build().is_err());
+    /// assert!(integer("id").primary(true).try_build().is_ok());
+    /// ```
+    pub fn try_build(self) -> Result<ColumnAddChange, SqlPressError> {
+        self.inner.validate()
+    }
+}
+
+/// Builder for a column with a foreign key (see [ColumnAddBuilder::references]).
+pub struct ColumnReferencesBuilder {
+    inner: ColumnAddChange,
+}
+
+impl ColumnReferencesBuilder {
+    fn foreign_key(&mut self) -> &mut IndexAddForeignChange {
+        self.inner
+            .foreign_key
+            .as_mut()
+            .expect("foreign key is set by ColumnAddBuilder::references")
+    }
+
+    /// Name of the foreign key constraint.
+    pub fn name(mut self, idx_name: &str) -> Self {
+        self.foreign_key().idx_name = Some(idx_name.into());
+
+        self
+    }
+
+    pub fn on_delete(mut self, action: ReferentialAction) -> Self {
+        self.foreign_key().options.on_delete = Some(action);
+
+        self
+    }
+
+    pub fn on_update(mut self, action: ReferentialAction) -> Self {
+        self.foreign_key().options.on_update = Some(action);
+
+        self
+    }
+
+    pub fn build(self) -> ColumnAddChange {
+        self.inner
+    }
+
+    /// See [ColumnAddBuilder::try_build].
+    pub fn try_build(self) -> Result<ColumnAddChange, SqlPressError> {
+        self.inner.validate()
+    }
+}
+
+/// Auto-incrementing integer primary key column. How it is rendered depends
+/// on the dialect (e.g.
+/// [AutoIncrement][crate::sql_dialect::postgres::AutoIncrement] on postgres).
+pub fn auto_id(name: &str) -> ColumnAddBuilder {
+    integer(name).primary(true).identity(true)
 }
 
 pub fn uuid(name: &str) -> ColumnAddBuilder {
@@ -158,8 +436,11 @@ pub fn bool(name: &str) -> ColumnAddBuilder {
     ColumnAddBuilder::new(name, ColumnType::BOOL)
 }
 
+/// Variable length character column. Without a `size` the column is
+/// unbounded, dialects which require a length apply their
+/// [UnboundedVarchar][crate::sql_dialect::UnboundedVarchar] policy.
 pub fn varchar(name: &str, size: Option<usize>) -> ColumnAddBuilder {
-    ColumnAddBuilder::new(name, ColumnType::VARCHAR(size.unwrap_or(255)))
+    ColumnAddBuilder::new(name, ColumnType::VARCHAR(size))
 }
 
 pub fn real(name: &str) -> ColumnAddBuilder {
@@ -171,34 +452,194 @@ pub fn text(name: &str) -> ColumnAddBuilder {
 }
 
 pub fn timestamp(name: &str) -> ColumnAddBuilder {
-    ColumnAddBuilder::new(name, ColumnType::TIMESTAMP)
+    ColumnAddBuilder::new(name, ColumnType::TIMESTAMP(None))
+}
+
+/// Timestamp column with the given fractional-second precision.
+pub fn timestamp_p(name: &str, precision: u8) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::TIMESTAMP(Some(precision)))
 }
 
 pub fn timestamp_tz(name: &str) -> ColumnAddBuilder {
-    ColumnAddBuilder::new(name, ColumnType::TIMESTAMPTZ)
+    ColumnAddBuilder::new(name, ColumnType::TIMESTAMPTZ(None))
+}
+
+/// Timestamp with time zone column with the given fractional-second
+/// precision.
+pub fn timestamp_tz_p(name: &str, precision: u8) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::TIMESTAMPTZ(Some(precision)))
+}
+
+pub fn time(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::TIME(None))
+}
+
+/// Time column with the given fractional-second precision.
+pub fn time_p(name: &str, precision: u8) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::TIME(Some(precision)))
+}
+
+pub fn time_tz(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::TIMETZ(None))
+}
+
+/// Time with time zone column with the given fractional-second precision.
+pub fn time_tz_p(name: &str, precision: u8) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::TIMETZ(Some(precision)))
 }
 
 pub fn integer(name: &str) -> ColumnAddBuilder {
     ColumnAddBuilder::new(name, ColumnType::INTEGER)
 }
 
+pub fn smallint(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::SMALLINT)
+}
+
+pub fn bigint(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::BIGINT)
+}
+
+/// Auto-incrementing integer column, backed by a sequence on postgres and an
+/// `IDENTITY` on T-SQL.
+pub fn serial(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::SERIAL)
+}
+
+/// Auto-incrementing bigint column (see [serial]).
+pub fn bigserial(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::BIGSERIAL)
+}
+
+/// Textual JSON column, prefer [jsonb] on postgres unless the exact input
+/// has to be preserved.
+pub fn json(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::JSON)
+}
+
+pub fn xml(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::XML)
+}
+
 pub fn jsonb(name: &str) -> ColumnAddBuilder {
     ColumnAddBuilder::new(name, ColumnType::JSONB)
 }
 
+/// Full-text search document column, usually indexed with
+/// [IndexMethod::Gin][crate::index::IndexMethod::Gin].
+pub fn tsvector(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::TSVECTOR)
+}
+
+/// Full-text search query column.
+pub fn tsquery(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::TSQUERY)
+}
+
+/// Case-insensitive text column, requires the `citext` extension on
+/// postgres (see [ChangeSet::create_extension][crate::change::ChangeSet::create_extension]).
+pub fn citext(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::CITEXT)
+}
+
+/// Key/value column, requires the `hstore` extension on postgres.
+pub fn hstore(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::HSTORE)
+}
+
+/// Label path column, requires the `ltree` extension on postgres.
+pub fn ltree(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::LTREE)
+}
+
+/// Variable length binary column, e.g. for encrypted payloads.
+pub fn bytea(name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::BINARY)
+}
+
+/// Column of a type, which is not modelled by this crate, e.g.
+/// `custom("location", "geography(Point,4326)")`. The type is rendered as is.
+pub fn custom(name: &str, sql_type: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::Custom(sql_type.into()))
+}
+
+/// Column of the given domain type.
+pub fn domain(name: &str, domain_name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::Domain(domain_name.into()))
+}
+
+/// Column of the given user-defined composite type.
+pub fn composite(name: &str, type_name: &str) -> ColumnAddBuilder {
+    ColumnAddBuilder::new(name, ColumnType::Composite(type_name.into()))
+}
+
 /// Available column types (still partially postgres specific). The crates user
 /// needs to be made aware of this fact.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ColumnType {
     UUID,
     BOOL,
-    VARCHAR(usize),
+    /// Optional maximum length, `None` is unbounded
+    VARCHAR(Option<usize>),
     REAL,
+    SMALLINT,
     INTEGER,
+    BIGINT,
+    /// Auto-incrementing `INTEGER`
+    SERIAL,
+    /// Auto-incrementing `BIGINT`
+    BIGSERIAL,
     TEXT,
-    TIMESTAMP,
-    TIMESTAMPTZ,
+    /// Optional fractional-second precision
+    TIMESTAMP(Option<u8>),
+    /// Optional fractional-second precision
+    TIMESTAMPTZ(Option<u8>),
+    /// Optional fractional-second precision
+    TIME(Option<u8>),
+    /// Optional fractional-second precision
+    TIMETZ(Option<u8>),
+    JSON,
     JSONB,
+    /// Preprocessed document for full-text search
+    TSVECTOR,
+    /// Full-text search query
+    TSQUERY,
+    /// Case-insensitive text (postgres `citext` extension)
+    CITEXT,
+    /// Key/value pairs (postgres `hstore` extension)
+    HSTORE,
+    /// Hierarchical label path (postgres `ltree` extension)
+    LTREE,
+    XML,
+    /// Variable length binary data
+    BINARY,
+    /// User-defined composite type (see
+    /// [ChangeSet::create_composite_type][crate::change::ChangeSet::create_composite_type])
+    Composite(String),
+    /// User-defined domain type (see
+    /// [ChangeSet::create_domain][crate::change::ChangeSet::create_domain])
+    Domain(String),
+    /// Enum with its type name and values. Rendered as native enum type or
+    /// emulated with a `CHECK` constraint, depending on the dialect.
+    Enum {
+        name: String,
+        values: Vec<String>,
+    },
+    /// Type, which is not modelled by this crate. Rendered as is by every
+    /// dialect.
+    Custom(String),
+}
+
+impl ColumnType {
+    /// Returns the postgres extension, which provides this type.
+    pub fn required_extension(&self) -> Option<&'static str> {
+        match self {
+            ColumnType::CITEXT => Some("citext"),
+            ColumnType::HSTORE => Some("hstore"),
+            ColumnType::LTREE => Some("ltree"),
+            _ => None,
+        }
+    }
 }
 
 pub trait ColumnAdd {
@@ -212,6 +653,11 @@ pub trait ColumnDrop {
 
 impl ColumnAdd for Table {
     fn add_column(&mut self, column: ColumnAddChange) {
+        let mut column = column;
+        self.name_column_constraints(&mut column);
+        if let Some(foreign_key) = column.foreign_key.take() {
+            IndexAdd::add_foreign_key(self, foreign_key);
+        }
         self.changes.push(Box::new(column));
     }
 }
@@ -232,12 +678,102 @@ impl ColumnDrop for Table {
     }
 }
 
-pub trait ColumnCreate: ColumnAdd + IndexAdd {}
-impl ColumnCreate for Table {}
+/// Change, which increments the `version` column of a table on every update
+/// (see [ColumnCreate::version_column]).
+#[derive(Debug)]
+pub struct VersionTriggerChange {
+    pub(crate) table_name: String,
+}
+
+impl Change for VersionTriggerChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.version_trigger(&self.table_name)
+    }
+}
+
+impl Table {
+    /// Adds a column of the enum type `{table}_{column}` with the given
+    /// values, together with the enum type itself.
+    fn add_enum(&mut self, with_prefix: bool, column_name: &str, values: &[&str]) {
+        let type_name = format!("{}_{}", self.name, column_name);
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+
+        self.pre_statements.push(Box::new(TypeChange::new(
+            TypeChangeOp::CreateEnum {
+                values: values.clone(),
+            },
+            &type_name,
+        )));
+
+        let mut column = ColumnAddChange::new(
+            column_name,
+            ColumnType::Enum {
+                name: type_name,
+                values,
+            },
+        );
+        column.with_prefix = with_prefix;
+        self.changes.push(Box::new(column));
+    }
+
+    /// Adds the `version` column for optimistic locking, optionally with a
+    /// trigger, which increments it on every update.
+    fn add_version_column(&mut self, with_prefix: bool, increment_on_update: bool) {
+        let mut column = integer("version")
+            .not_null(true)
+            .default(DefaultConstraint::Expr(Expr::val(0)))
+            .build();
+        column.with_prefix = with_prefix;
+        self.changes.push(Box::new(column));
+
+        if increment_on_update {
+            self.statements.push(Box::new(VersionTriggerChange {
+                table_name: self.name.clone(),
+            }));
+        }
+    }
+}
+
+pub trait ColumnCreate: ColumnAdd + IndexAdd {
+    /// Adds a column, which only accepts the given values. Renders a native
+    /// enum type `{table}_{column}` where supported, otherwise a `CHECK`
+    /// constraint on the column.
+    fn add_enum_column(&mut self, column_name: &str, values: &[&str]);
+
+    /// Adds an integer `version NOT NULL DEFAULT 0` column for optimistic
+    /// locking. With `increment_on_update` a trigger is created, which
+    /// increments the version on every update of a row.
+    fn version_column(&mut self, increment_on_update: bool);
+
+    /// Declares the order of the columns in the `CREATE TABLE` statement,
+    /// independent of the order in which they were added. Columns which are
+    /// not listed follow in the order they were added.
+    fn column_order(&mut self, columns: &[&str]);
+}
+
+impl ColumnCreate for Table {
+    fn add_enum_column(&mut self, column_name: &str, values: &[&str]) {
+        self.add_enum(false, column_name, values);
+    }
+
+    fn version_column(&mut self, increment_on_update: bool) {
+        self.add_version_column(false, increment_on_update);
+    }
+
+    fn column_order(&mut self, columns: &[&str]) {
+        self.column_order = columns.iter().map(|c| c.to_string()).collect();
+    }
+}
 
 pub trait ColumnAlter: ColumnDrop + IndexAlter {
     fn add_column(&mut self, column: ColumnAddChange);
 
+    /// See [ColumnCreate::add_enum_column].
+    fn add_enum_column(&mut self, column_name: &str, values: &[&str]);
+
+    /// See [ColumnCreate::version_column].
+    fn version_column(&mut self, increment_on_update: bool);
+
     fn rename_column(&mut self, column_name: &str, new_column_name: &str);
 
     fn alter_column(
@@ -246,17 +782,38 @@ pub trait ColumnAlter: ColumnDrop + IndexAlter {
         new_column_type: ColumnType,
         conversion_method: Option<String>,
     );
+
+    /// Sets the storage strategy of the column, e.g. [Storage::External] to
+    /// store large values uncompressed and out of line.
+    fn set_column_storage(&mut self, column_name: &str, storage: Storage);
+
+    /// Sets the compression method (e.g. `lz4`) of the column, which is used
+    /// for newly stored values.
+    fn set_column_compression(&mut self, column_name: &str, compression: &str);
 }
 
 impl ColumnAlter for Table {
     fn add_column(&mut self, column: ColumnAddChange) {
         let mut alter_column = column;
         alter_column.with_prefix = true;
+        self.name_column_constraints(&mut alter_column);
+        if let Some(foreign_key) = alter_column.foreign_key.take() {
+            IndexAlter::add_foreign_key(self, foreign_key);
+        }
         self.changes.push(Box::new(alter_column));
     }
 
+    fn add_enum_column(&mut self, column_name: &str, values: &[&str]) {
+        self.add_enum(true, column_name, values);
+    }
+
+    fn version_column(&mut self, increment_on_update: bool) {
+        self.add_version_column(true, increment_on_update);
+    }
+
     fn rename_column(&mut self, name: &str, new_name: &str) {
         self.changes.push(Box::new(ColumnRenameChange {
+            table_name: self.name.clone(),
             name: name.into(),
             new_name: new_name.into(),
         }))
@@ -274,6 +831,20 @@ impl ColumnAlter for Table {
             conversion_method,
         }))
     }
+
+    fn set_column_storage(&mut self, column_name: &str, storage: Storage) {
+        self.changes.push(Box::new(ColumnStorageChange {
+            name: column_name.into(),
+            storage,
+        }))
+    }
+
+    fn set_column_compression(&mut self, column_name: &str, compression: &str) {
+        self.changes.push(Box::new(ColumnCompressionChange {
+            name: column_name.into(),
+            compression: compression.into(),
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -301,8 +872,40 @@ mod tests {
 
         let col: &ColumnAddChange = get_downcasted_column_change(&t, 0);
         let col2: &ColumnAddChange = get_downcasted_column_change(&t, 2);
-        assert!(col.with_prefix == false);
-        assert!(col2.with_prefix == true);
+        assert!(!col.with_prefix);
+        assert!(col2.with_prefix);
+    }
+
+    #[test]
+    fn try_build() {
+        assert_eq!(
+            uuid("id")
+                .primary(true)
+                .unique(true)
+                .try_build()
+                .unwrap_err(),
+            SqlPressError::InvalidChange(
+                "Column id is the primary key and therefore already unique".into()
+            )
+        );
+        assert!(text("name")
+            .not_null(true)
+            .default(DefaultConstraint::value(Value::Null))
+            .try_build()
+            .is_err());
+        assert!(auto_id("id")
+            .default(DefaultConstraint::Plain("1".into()))
+            .try_build()
+            .is_err());
+        assert!(text("name")
+            .default(DefaultConstraint::Plain("NULL".into()))
+            .try_build()
+            .is_ok());
+        assert!(text("slug")
+            .generated(Expr::func("lower", vec![Expr::col("name")]))
+            .default(DefaultConstraint::value(""))
+            .try_build()
+            .is_err());
     }
 
     #[test]
@@ -317,9 +920,9 @@ mod tests {
         let col2: &ColumnAlterChange = get_downcasted_column_change(&t, 1);
         let col3: &ColumnRenameChange = get_downcasted_column_change(&t, 2);
 
-        assert!(col.ct == ColumnType::VARCHAR(255));
+        assert!(col.ct == ColumnType::VARCHAR(None));
         assert!(col2.ct == ColumnType::UUID);
-        assert!(col3.new_name == "id3".to_string());
+        assert!(col3.new_name == "id3");
     }
 
     #[test]
@@ -331,10 +934,10 @@ mod tests {
 
         let col: &ColumnDropChange = get_downcasted_column_change(&t, 0);
         let col2: &ColumnDropChange = get_downcasted_column_change(&t, 1);
-        assert!(col.if_exists == false);
-        assert!(col.name == "test".to_string());
-        assert!(col2.if_exists == true);
-        assert!(col2.name == "test".to_string());
+        assert!(!col.if_exists);
+        assert!(col.name == "test");
+        assert!(col2.if_exists);
+        assert!(col2.name == "test");
     }
 
     #[test]
@@ -344,17 +947,23 @@ mod tests {
         assert_eq!(cb.inner.name, "id");
         assert_eq!(cb.inner.ct, ColumnType::UUID);
 
-        assert_eq!(cb.inner.constraints.primary, false);
-        assert_eq!(cb.inner.constraints.not_null, false);
-        assert_eq!(cb.inner.constraints.unique, false);
+        assert!(!cb.inner.constraints.primary);
+        assert!(!cb.inner.constraints.not_null);
+        assert!(!cb.inner.constraints.unique);
 
         let cb = cb.primary(true);
-        assert_eq!(cb.inner.constraints.primary, true);
+        assert!(cb.inner.constraints.primary);
 
         let cb = cb.not_null(true);
-        assert_eq!(cb.inner.constraints.not_null, true);
+        assert!(cb.inner.constraints.not_null);
 
         let cb = cb.unique(true);
-        assert_eq!(cb.inner.constraints.unique, true);
+        assert!(cb.inner.constraints.unique);
+
+        let cb = cb.identity(true);
+        assert!(cb.inner.constraints.identity);
+
+        let cb = cb.check(Expr::col("id").is_not_null());
+        assert!(cb.inner.constraints.check.is_some());
     }
 }
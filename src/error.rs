@@ -0,0 +1,56 @@
+//! Provides the [SqlPressError] type, which is returned by the DDL generation
+//! (see [ChangeSet::get_ddl][crate::change::ChangeSet::get_ddl]).
+use std::{error::Error, fmt};
+
+/// Result of the DDL generation.
+pub type Result<T, E = SqlPressError> = std::result::Result<T, E>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlPressError {
+    /// The dialect does not support the change
+    UnsupportedByDialect(String),
+    /// The change can't be rendered, e.g. because it violates a limit of the
+    /// dialect
+    InvalidChange(String),
+}
+
+impl SqlPressError {
+    #[cfg(any(feature = "postgres", feature = "mssql"))]
+    pub(crate) fn unsupported(msg: impl Into<String>) -> Self {
+        SqlPressError::UnsupportedByDialect(msg.into())
+    }
+
+    pub(crate) fn invalid(msg: impl Into<String>) -> Self {
+        SqlPressError::InvalidChange(msg.into())
+    }
+}
+
+impl fmt::Display for SqlPressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlPressError::UnsupportedByDialect(msg) => {
+                write!(f, "change is not supported by the dialect: {}", msg)
+            }
+            SqlPressError::InvalidChange(msg) => write!(f, "invalid change: {}", msg),
+        }
+    }
+}
+
+impl Error for SqlPressError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            SqlPressError::UnsupportedByDialect("T-SQL does not support x".into()).to_string(),
+            "change is not supported by the dialect: T-SQL does not support x"
+        );
+        assert_eq!(
+            SqlPressError::invalid("too long").to_string(),
+            "invalid change: too long"
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Provides a small expression builder for positions, where SQL expressions
+//! are required (e.g. column defaults, `CHECK` constraints, partial index
+//! predicates and generated columns). Expressions are rendered per dialect,
+//! so identifiers and literals are always quoted correctly.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "postgres")] {
+//! use sql_press::{expr::Expr, sql_dialect::{Postgres, SqlDialect}};
+//!
+//! let e = Expr::col("price")
+//!     .gt(Expr::val(0))
+//!     .and(Expr::col("currency").is_not_null());
+//!
+//! assert_eq!(
+//!     Postgres::new().expr(&e).unwrap(),
+//!     r#""price" > 0 AND "currency" IS NOT NULL"#
+//! );
+//! # }
+//! ```
+
+#[cfg(any(feature = "postgres", feature = "mssql"))]
+use crate::{
+    error::{Result, SqlPressError},
+    ident::Ident,
+};
+
+/// Literal value within an [Expr].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    /// Floating point number. `NaN` and infinite values have no literal
+    /// representation and are rejected when rendered.
+    Real(f64),
+    Text(String),
+}
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Value::Bool(v)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(v: i32) -> Self {
+        Value::Int(v.into())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(v: i64) -> Self {
+        Value::Int(v)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Value::Real(v)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::Text(v.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::Text(v)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Eq,
+    NotEq,
+    Gt,
+    GtEq,
+    Lt,
+    LtEq,
+    And,
+    Or,
+}
+
+#[cfg(any(feature = "postgres", feature = "mssql"))]
+impl BinaryOp {
+    fn sql(&self) -> &'static str {
+        match self {
+            BinaryOp::Eq => "=",
+            BinaryOp::NotEq => "<>",
+            BinaryOp::Gt => ">",
+            BinaryOp::GtEq => ">=",
+            BinaryOp::Lt => "<",
+            BinaryOp::LtEq => "<=",
+            BinaryOp::And => "AND",
+            BinaryOp::Or => "OR",
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            _ => 3,
+        }
+    }
+}
+
+/// Dialect independent SQL expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Column(String),
+    Value(Value),
+    /// Function call with its arguments
+    Function(String, Vec<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    Not(Box<Expr>),
+    IsNull(Box<Expr>),
+    IsNotNull(Box<Expr>),
+    /// Plain SQL, which is rendered as is
+    Raw(String),
+}
+
+impl Expr {
+    pub fn col(name: &str) -> Self {
+        Expr::Column(name.into())
+    }
+
+    pub fn val<V: Into<Value>>(value: V) -> Self {
+        Expr::Value(value.into())
+    }
+
+    pub fn null() -> Self {
+        Expr::Value(Value::Null)
+    }
+
+    pub fn func(name: &str, args: Vec<Expr>) -> Self {
+        Expr::Function(name.into(), args)
+    }
+
+    /// Escape hatch for expressions, which are not (yet) supported by the
+    /// builder. The given SQL is not validated or translated in any way.
+    pub fn raw(sql: &str) -> Self {
+        Expr::Raw(sql.into())
+    }
+
+    pub fn eq(self, other: Expr) -> Self {
+        self.binary(BinaryOp::Eq, other)
+    }
+
+    pub fn not_eq(self, other: Expr) -> Self {
+        self.binary(BinaryOp::NotEq, other)
+    }
+
+    pub fn gt(self, other: Expr) -> Self {
+        self.binary(BinaryOp::Gt, other)
+    }
+
+    pub fn gt_eq(self, other: Expr) -> Self {
+        self.binary(BinaryOp::GtEq, other)
+    }
+
+    pub fn lt(self, other: Expr) -> Self {
+        self.binary(BinaryOp::Lt, other)
+    }
+
+    pub fn lt_eq(self, other: Expr) -> Self {
+        self.binary(BinaryOp::LtEq, other)
+    }
+
+    pub fn and(self, other: Expr) -> Self {
+        self.binary(BinaryOp::And, other)
+    }
+
+    pub fn or(self, other: Expr) -> Self {
+        self.binary(BinaryOp::Or, other)
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Expr::Not(Box::new(self))
+    }
+
+    pub fn is_null(self) -> Self {
+        Expr::IsNull(Box::new(self))
+    }
+
+    pub fn is_not_null(self) -> Self {
+        Expr::IsNotNull(Box::new(self))
+    }
+
+    fn binary(self, op: BinaryOp, other: Expr) -> Self {
+        Expr::Binary(Box::new(self), op, Box::new(other))
+    }
+}
+
+#[cfg(any(feature = "postgres", feature = "mssql"))]
+impl Expr {
+    /// Renders the expression with the dialect specific [ExprStyle].
+    pub(crate) fn render(&self, style: &ExprStyle) -> Result<String> {
+        Ok(match self {
+            Expr::Column(name) => {
+                let ident = Ident::new(name);
+                ident.validate()?;
+                ident.quoted(style.quote.0, style.quote.1)
+            }
+            Expr::Value(v) => match v {
+                Value::Null => "NULL".into(),
+                Value::Bool(b) => if *b {
+                    style.true_literal
+                } else {
+                    style.false_literal
+                }
+                .into(),
+                Value::Int(i) => i.to_string(),
+                Value::Real(r) if !r.is_finite() => {
+                    return Err(SqlPressError::invalid(format!(
+                        "{} can't be rendered as a literal",
+                        r
+                    )))
+                }
+                Value::Real(r) => r.to_string(),
+                Value::Text(t) => format!("'{}'", t.replace('\'', "''")),
+            },
+            Expr::Function(name, args) => format!(
+                "{}({})",
+                name,
+                args.iter()
+                    .map(|a| a.render(style))
+                    .collect::<Result<Vec<String>>>()?
+                    .join(", ")
+            ),
+            Expr::Binary(l, op, r) => format!(
+                "{} {} {}",
+                l.render_operand(op, style)?,
+                op.sql(),
+                r.render_operand(op, style)?
+            ),
+            Expr::Not(e) => format!("NOT ({})", e.render(style)?),
+            Expr::IsNull(e) => format!("{} IS NULL", e.render_operand(&BinaryOp::Eq, style)?),
+            Expr::IsNotNull(e) => {
+                format!("{} IS NOT NULL", e.render_operand(&BinaryOp::Eq, style)?)
+            }
+            Expr::Raw(sql) => sql.clone(),
+        })
+    }
+
+    /// Renders the expression as operand of `parent`, wrapped in parentheses
+    /// if it binds weaker than the parent operator.
+    fn render_operand(&self, parent: &BinaryOp, style: &ExprStyle) -> Result<String> {
+        match self {
+            Expr::Binary(_, op, _) if op.precedence() < parent.precedence() => {
+                Ok(format!("({})", self.render(style)?))
+            }
+            _ => self.render(style),
+        }
+    }
+}
+
+/// Dialect specific details, which are needed to render an [Expr].
+#[cfg(any(feature = "postgres", feature = "mssql"))]
+pub(crate) struct ExprStyle {
+    pub(crate) quote: (char, char),
+    pub(crate) true_literal: &'static str,
+    pub(crate) false_literal: &'static str,
+}
+
+#[cfg(all(test, any(feature = "postgres", feature = "mssql")))]
+mod tests {
+    use super::*;
+
+    const STYLE: ExprStyle = ExprStyle {
+        quote: ('"', '"'),
+        true_literal: "TRUE",
+        false_literal: "FALSE",
+    };
+
+    #[test]
+    fn literals() {
+        assert_eq!(Expr::val("it's").render(&STYLE).unwrap(), "'it''s'");
+        assert_eq!(Expr::val(true).render(&STYLE).unwrap(), "TRUE");
+        assert_eq!(Expr::val(1.5).render(&STYLE).unwrap(), "1.5");
+        assert_eq!(Expr::null().render(&STYLE).unwrap(), "NULL");
+        assert_eq!(Expr::val(-2.5e10).render(&STYLE).unwrap(), "-25000000000");
+    }
+
+    #[test]
+    fn non_finite_reals() {
+        for r in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(matches!(
+                Expr::val(r).render(&STYLE),
+                Err(SqlPressError::InvalidChange(_))
+            ));
+        }
+        assert!(Expr::col("a")
+            .gt(Expr::val(f64::NAN))
+            .render(&STYLE)
+            .is_err());
+    }
+
+    #[test]
+    fn precedence() {
+        let e = Expr::col("a")
+            .eq(Expr::val(1))
+            .or(Expr::col("b").eq(Expr::val(2)))
+            .and(Expr::col("c").is_null());
+        assert_eq!(
+            e.render(&STYLE).unwrap(),
+            "(\"a\" = 1 OR \"b\" = 2) AND \"c\" IS NULL"
+        );
+    }
+
+    #[test]
+    fn function() {
+        let e = Expr::func("lower", vec![Expr::col("email")]).not_eq(Expr::val(""));
+        assert_eq!(e.render(&STYLE).unwrap(), "lower(\"email\") <> ''");
+        assert_eq!(
+            Expr::col("active").not().render(&STYLE).unwrap(),
+            "NOT (\"active\")"
+        );
+    }
+}
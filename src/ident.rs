@@ -0,0 +1,138 @@
+//! Provides the [Ident] type, which centralizes how identifiers (tables,
+//! columns, indexes, constraints, ...) are validated and quoted.
+#[cfg(any(feature = "postgres", feature = "mssql"))]
+use crate::error::{Result, SqlPressError};
+
+/// SQL identifier. Identifiers are validated and quoted by the dialect (see
+/// [SqlDialect::quote_ident][crate::sql_dialect::SqlDialect::quote_ident]),
+/// which also escapes embedded quote characters. Invalid identifiers are
+/// reported as [SqlPressError::InvalidChange][crate::error::SqlPressError::InvalidChange]
+/// when the DDL is rendered.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "postgres")] {
+/// use sql_press::{error::SqlPressError, ident::Ident, sql_dialect::{Postgres, SqlDialect}};
+///
+/// let d = Postgres::new();
+/// assert_eq!(d.quote_ident(&Ident::new("my \"table\"")).unwrap(), r#""my ""table""""#);
+/// assert_eq!(d.quote_ident(&Ident::raw("lower(email)")).unwrap(), "lower(email)");
+/// assert!(matches!(
+///     d.quote_ident(&Ident::new("")),
+///     Err(SqlPressError::InvalidChange(_))
+/// ));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ident {
+    name: String,
+    raw: bool,
+}
+
+impl Ident {
+    /// Creates a new identifier, which is validated and quoted when rendered.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            raw: false,
+        }
+    }
+
+    /// Escape hatch for identifiers, which must be rendered as is, e.g.
+    /// expressions or names which are already quoted. Raw identifiers are
+    /// neither validated nor quoted. Index columns accept raw identifiers
+    /// (see [IndexColumn][crate::index::IndexColumn]).
+    pub fn raw(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            raw: true,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    #[cfg(any(feature = "postgres", feature = "mssql"))]
+    pub(crate) fn is_raw(&self) -> bool {
+        self.raw
+    }
+
+    /// Fails with [SqlPressError::InvalidChange], if the identifier is empty
+    /// or contains a NUL character. Raw identifiers are never rejected.
+    #[cfg(any(feature = "postgres", feature = "mssql"))]
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.raw {
+            return Ok(());
+        }
+        if self.name.is_empty() {
+            return Err(SqlPressError::invalid("identifiers must not be empty"));
+        }
+        if self.name.contains('\0') {
+            return Err(SqlPressError::invalid(format!(
+                "identifiers must not contain NUL characters: {:?}",
+                self.name
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Quotes the identifier with the given quote characters, doubling every
+    /// embedded closing quote character.
+    #[cfg(any(feature = "postgres", feature = "mssql"))]
+    pub(crate) fn quoted(&self, open: char, close: char) -> String {
+        if self.raw {
+            return self.name.clone();
+        }
+
+        format!(
+            "{}{}{}",
+            open,
+            self.name.replace(close, &format!("{}{}", close, close)),
+            close
+        )
+    }
+}
+
+impl From<&str> for Ident {
+    fn from(name: &str) -> Self {
+        Ident::new(name)
+    }
+}
+
+impl From<&String> for Ident {
+    fn from(name: &String) -> Self {
+        Ident::new(name)
+    }
+}
+
+impl From<String> for Ident {
+    fn from(name: String) -> Self {
+        Self { name, raw: false }
+    }
+}
+
+#[cfg(all(test, any(feature = "postgres", feature = "mssql")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted() {
+        assert_eq!(Ident::new("tag").quoted('"', '"'), "\"tag\"");
+        assert_eq!(Ident::new("a\"b").quoted('"', '"'), "\"a\"\"b\"");
+        assert_eq!(Ident::new("a]b").quoted('[', ']'), "[a]]b]");
+        assert_eq!(Ident::raw("a\"b").quoted('"', '"'), "a\"b");
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(Ident::new("tag").validate(), Ok(()));
+        assert_eq!(
+            Ident::new("").validate(),
+            Err(SqlPressError::invalid("identifiers must not be empty"))
+        );
+        assert!(Ident::new("a\0b").validate().is_err());
+        assert_eq!(Ident::raw("").validate(), Ok(()));
+    }
+}
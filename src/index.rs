@@ -1,9 +1,14 @@
 //! Provides column index related operations.
 use std::rc::Rc;
 
-use crate::{change::Change, sql_dialect::SqlDialect, table::Table};
+use crate::{
+    change::Change, error::Result, expr::Expr, ident::Ident, sql_dialect::SqlDialect, table::Table,
+};
 
 pub trait IndexAdd {
+    /// Adds a foreign key, which was defined via [foreign_key].
+    fn add_foreign_key(&mut self, foreign_key: IndexAddForeignChange);
+
     fn add_foreign_index(
         &mut self,
         column_name: &str,
@@ -12,12 +17,75 @@ pub trait IndexAdd {
         idx_name: Option<String>,
     );
 
+    /// Adds a foreign key, which spans multiple columns. The columns are
+    /// matched by their position with the `foreign_columns`.
+    ///
+    /// # Example
+    /// ```
+    /// use sql_press::{change::ChangeSet, column::uuid};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.create_table("order", |t| {
+    ///     t.add_column(uuid("tenant_id").not_null(true).build());
+    ///     t.add_column(uuid("user_id").not_null(true).build());
+    ///     t.add_foreign_index_composite(
+    ///         &["tenant_id", "user_id"],
+    ///         "user",
+    ///         &["tenant_id", "id"],
+    ///         None,
+    ///     );
+    /// });
+    /// ```
+    fn add_foreign_index_composite(
+        &mut self,
+        columns: &[&str],
+        foreign_table_name: &str,
+        foreign_columns: &[&str],
+        idx_name: Option<String>,
+    );
+
     fn add_primary_index(&mut self, columns: Vec<&str>);
 
+    /// Same as add_primary_index, but names the primary key constraint.
+    fn add_primary_index_named(&mut self, constraint_name: &str, columns: Vec<&str>);
+
+    /// Adds a unique constraint, which was defined via [unique_constraint].
+    fn add_unique(&mut self, unique: IndexAddUniqueChange);
+
     fn add_unique_constraint(&mut self, constraint_name: &str, columns: Vec<&str>);
+
+    /// Adds a named `CHECK` constraint, which may span multiple columns.
+    fn add_check_constraint(&mut self, constraint_name: &str, condition: Expr);
+
+    /// Adds an index on the given columns of the table, which is created
+    /// with a separate `CREATE INDEX` statement after the table statement.
+    /// For more options, use [ChangeSet::create_index][crate::change::ChangeSet::create_index].
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, column::varchar, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.create_table("users", |t| {
+    ///     t.add_column(varchar("email", None).build());
+    ///     t.add_index(&["email"], Some("idx_users_email"));
+    /// });
+    ///
+    /// assert_eq!(r#"CREATE TABLE "public"."users" (
+    /// "email" VARCHAR
+    /// );
+    ///
+    /// CREATE INDEX "idx_users_email" ON "public"."users" ("email");"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    fn add_index(&mut self, columns: &[&str], idx_name: Option<&str>);
 }
 
 pub trait IndexAlter {
+    /// Adds a foreign key, which was defined via [foreign_key].
+    fn add_foreign_key(&mut self, foreign_key: IndexAddForeignChange);
+
     fn add_foreign_index(
         &mut self,
         column_name: &str,
@@ -26,12 +94,83 @@ pub trait IndexAlter {
         idx_name: Option<String>,
     );
 
+    /// See [IndexAdd::add_foreign_index_composite].
+    fn add_foreign_index_composite(
+        &mut self,
+        columns: &[&str],
+        foreign_table_name: &str,
+        foreign_columns: &[&str],
+        idx_name: Option<String>,
+    );
+
     fn add_primary_index(&mut self, columns: Vec<&str>);
 
+    /// Same as add_primary_index, but names the primary key constraint.
+    fn add_primary_index_named(&mut self, constraint_name: &str, columns: Vec<&str>);
+
+    /// Adds a unique constraint, which was defined via [unique_constraint].
+    fn add_unique(&mut self, unique: IndexAddUniqueChange);
+
     fn add_unique_constraint(&mut self, constraint_name: &str, columns: Vec<&str>);
+
+    /// Promotes an existing unique index (e.g. created concurrently) to a
+    /// unique constraint.
+    fn add_unique_constraint_using_index(&mut self, constraint_name: &str, index_name: &str);
+
+    /// Promotes an existing unique index (e.g. created concurrently) to the
+    /// primary key of the table.
+    fn add_primary_index_using_index(&mut self, index_name: &str);
+
+    fn drop_unique_constraint(&mut self, constraint_name: &str);
+
+    /// Adds a named `CHECK` constraint, which may span multiple columns.
+    fn add_check_constraint(&mut self, constraint_name: &str, condition: Expr);
+
+    /// Same as [IndexAlter::add_check_constraint], but doesn't check the
+    /// existing rows, which avoids a long lock on large tables. The
+    /// constraint is validated afterwards with
+    /// [IndexAlter::validate_constraint].
+    fn add_check_constraint_not_valid(&mut self, constraint_name: &str, condition: Expr);
+
+    fn drop_check_constraint(&mut self, constraint_name: &str);
+
+    /// Drops a constraint of any kind (e.g. a foreign key) by its name.
+    fn drop_constraint(&mut self, constraint_name: &str);
+
+    /// Same as [IndexAlter::drop_constraint], but optionally renders
+    /// `IF EXISTS` and `CASCADE`, which also drops dependent objects.
+    fn drop_constraint_with(&mut self, constraint_name: &str, if_exists: bool, cascade: bool);
+
+    fn rename_constraint(&mut self, constraint_name: &str, new_constraint_name: &str);
+
+    /// Validates a constraint, which was added without validating the
+    /// existing rows (see [ForeignKeyBuilder::not_valid]).
+    fn validate_constraint(&mut self, constraint_name: &str);
+
+    /// Drops the primary key of the table. Without a `constraint_name` the
+    /// name is derived by the dialect (e.g. `<table>_pkey` on postgres).
+    fn drop_primary_key(&mut self, constraint_name: Option<&str>);
+
+    /// See [IndexAdd::add_index].
+    fn add_index(&mut self, columns: &[&str], idx_name: Option<&str>);
+
+    /// Drops an index of the table. Depending on the dialect this is rendered
+    /// within the `ALTER TABLE` statement or as a separate statement after it.
+    fn drop_index(&mut self, index_name: &str);
+
+    /// Same as [IndexAlter::drop_index], but optionally renders `IF EXISTS`
+    /// and `CONCURRENTLY`. Dropping an index concurrently must not be executed
+    /// inside a transaction.
+    fn drop_index_with(&mut self, index_name: &str, if_exists: bool, concurrently: bool);
 }
 
 impl IndexAdd for Table {
+    fn add_foreign_key(&mut self, foreign_key: IndexAddForeignChange) {
+        let mut foreign_key = foreign_key;
+        self.name_foreign_key(&mut foreign_key);
+        self.idx_changes.push(Box::new(foreign_key));
+    }
+
     fn add_foreign_index(
         &mut self,
         column_name: &str,
@@ -39,30 +178,74 @@ impl IndexAdd for Table {
         foreign_column_name: &str,
         idx_name: Option<String>,
     ) {
-        self.idx_changes.push(Box::new(IndexAddForeignChange {
-            column_name: column_name.into(),
-            foreign_table_name: foreign_table_name.into(),
-            foreign_column_name: foreign_column_name.into(),
-            idx_name,
-            add_clause: false,
-        }));
+        let mut foreign_key =
+            IndexAddForeignChange::new(column_name, foreign_table_name, foreign_column_name);
+        foreign_key.idx_name = idx_name;
+        IndexAdd::add_foreign_key(self, foreign_key);
+    }
+
+    fn add_foreign_index_composite(
+        &mut self,
+        columns: &[&str],
+        foreign_table_name: &str,
+        foreign_columns: &[&str],
+        idx_name: Option<String>,
+    ) {
+        let mut foreign_key =
+            IndexAddForeignChange::new_composite(columns, foreign_table_name, foreign_columns);
+        foreign_key.idx_name = idx_name;
+        IndexAdd::add_foreign_key(self, foreign_key);
     }
 
     fn add_primary_index(&mut self, columns: Vec<&str>) {
         self.idx_changes.push(Box::new(IndexAddPrimaryChange {
+            constraint_name: None,
+            columns: columns.iter().map(|i| i.to_string()).collect(),
+            add_clause: false,
+        }))
+    }
+
+    fn add_primary_index_named(&mut self, constraint_name: &str, columns: Vec<&str>) {
+        self.idx_changes.push(Box::new(IndexAddPrimaryChange {
+            constraint_name: Some(constraint_name.to_string()),
             columns: columns.iter().map(|i| i.to_string()).collect(),
+            add_clause: false,
         }))
     }
 
+    fn add_unique(&mut self, unique: IndexAddUniqueChange) {
+        let mut unique = unique;
+        unique.add_clause = false;
+        self.idx_changes.push(Box::new(unique));
+    }
+
     fn add_unique_constraint(&mut self, constraint_name: &str, columns: Vec<&str>) {
-        self.idx_changes.push(Box::new(IndexAddUniqueChange {
+        IndexAdd::add_unique(self, IndexAddUniqueChange::new(constraint_name, columns));
+    }
+
+    fn add_check_constraint(&mut self, constraint_name: &str, condition: Expr) {
+        self.idx_changes.push(Box::new(IndexAddCheckChange {
             constraint_name: constraint_name.to_string(),
-            columns: columns.iter().map(|i| i.to_string()).collect(),
+            condition,
+            add_clause: false,
+            not_valid: false,
         }))
     }
+
+    fn add_index(&mut self, columns: &[&str], idx_name: Option<&str>) {
+        let index = self.index(columns, idx_name);
+        self.statements.push(Box::new(index));
+    }
 }
 
 impl IndexAlter for Table {
+    fn add_foreign_key(&mut self, foreign_key: IndexAddForeignChange) {
+        let mut foreign_key = foreign_key;
+        foreign_key.add_clause = true;
+        self.name_foreign_key(&mut foreign_key);
+        self.idx_changes.push(Box::new(foreign_key));
+    }
+
     fn add_foreign_index(
         &mut self,
         column_name: &str,
@@ -70,82 +253,1049 @@ impl IndexAlter for Table {
         foreign_column_name: &str,
         idx_name: Option<String>,
     ) {
-        self.idx_changes.push(Box::new(IndexAddForeignChange {
-            column_name: column_name.into(),
-            foreign_table_name: foreign_table_name.into(),
-            foreign_column_name: foreign_column_name.into(),
-            idx_name,
-            add_clause: true,
-        }));
+        let mut foreign_key =
+            IndexAddForeignChange::new(column_name, foreign_table_name, foreign_column_name);
+        foreign_key.idx_name = idx_name;
+        IndexAlter::add_foreign_key(self, foreign_key);
+    }
+
+    fn add_foreign_index_composite(
+        &mut self,
+        columns: &[&str],
+        foreign_table_name: &str,
+        foreign_columns: &[&str],
+        idx_name: Option<String>,
+    ) {
+        let mut foreign_key =
+            IndexAddForeignChange::new_composite(columns, foreign_table_name, foreign_columns);
+        foreign_key.idx_name = idx_name;
+        IndexAlter::add_foreign_key(self, foreign_key);
     }
 
     fn add_primary_index(&mut self, columns: Vec<&str>) {
         self.idx_changes.push(Box::new(IndexAddPrimaryChange {
+            constraint_name: None,
+            columns: columns.iter().map(|i| i.to_string()).collect(),
+            add_clause: true,
+        }))
+    }
+
+    fn add_primary_index_named(&mut self, constraint_name: &str, columns: Vec<&str>) {
+        self.idx_changes.push(Box::new(IndexAddPrimaryChange {
+            constraint_name: Some(constraint_name.to_string()),
             columns: columns.iter().map(|i| i.to_string()).collect(),
+            add_clause: true,
         }))
     }
 
+    fn add_unique(&mut self, unique: IndexAddUniqueChange) {
+        let mut unique = unique;
+        unique.add_clause = true;
+        self.idx_changes.push(Box::new(unique));
+    }
+
     fn add_unique_constraint(&mut self, constraint_name: &str, columns: Vec<&str>) {
-        self.idx_changes.push(Box::new(IndexAddUniqueChange {
+        IndexAlter::add_unique(self, IndexAddUniqueChange::new(constraint_name, columns));
+    }
+
+    fn add_unique_constraint_using_index(&mut self, constraint_name: &str, index_name: &str) {
+        self.idx_changes.push(Box::new(IndexAddUsingIndexChange {
+            constraint_name: Some(constraint_name.to_string()),
+            index_name: index_name.to_string(),
+            primary: false,
+        }))
+    }
+
+    fn add_primary_index_using_index(&mut self, index_name: &str) {
+        self.idx_changes.push(Box::new(IndexAddUsingIndexChange {
+            constraint_name: None,
+            index_name: index_name.to_string(),
+            primary: true,
+        }))
+    }
+
+    fn drop_unique_constraint(&mut self, constraint_name: &str) {
+        self.drop_changes.push(Box::new(IndexDropUniqueChange {
             constraint_name: constraint_name.to_string(),
-            columns: columns.iter().map(|i| i.to_string()).collect(),
         }))
     }
+
+    fn add_check_constraint(&mut self, constraint_name: &str, condition: Expr) {
+        self.idx_changes.push(Box::new(IndexAddCheckChange {
+            constraint_name: constraint_name.to_string(),
+            condition,
+            add_clause: true,
+            not_valid: false,
+        }))
+    }
+
+    fn add_check_constraint_not_valid(&mut self, constraint_name: &str, condition: Expr) {
+        self.idx_changes.push(Box::new(IndexAddCheckChange {
+            constraint_name: constraint_name.to_string(),
+            condition,
+            add_clause: true,
+            not_valid: true,
+        }))
+    }
+
+    fn drop_check_constraint(&mut self, constraint_name: &str) {
+        self.drop_changes.push(Box::new(IndexDropCheckChange {
+            constraint_name: constraint_name.to_string(),
+        }))
+    }
+
+    fn drop_constraint(&mut self, constraint_name: &str) {
+        self.drop_constraint_with(constraint_name, false, false);
+    }
+
+    fn drop_constraint_with(&mut self, constraint_name: &str, if_exists: bool, cascade: bool) {
+        self.drop_changes.push(Box::new(IndexDropConstraintChange {
+            constraint_name: constraint_name.to_string(),
+            if_exists,
+            cascade,
+        }))
+    }
+
+    fn validate_constraint(&mut self, constraint_name: &str) {
+        self.idx_changes
+            .push(Box::new(IndexValidateConstraintChange {
+                constraint_name: constraint_name.to_string(),
+            }))
+    }
+
+    fn drop_primary_key(&mut self, constraint_name: Option<&str>) {
+        self.drop_changes.push(Box::new(IndexDropPrimaryChange {
+            table_name: self.name.clone(),
+            constraint_name: constraint_name.map(|n| n.to_string()),
+        }))
+    }
+
+    fn rename_constraint(&mut self, constraint_name: &str, new_constraint_name: &str) {
+        self.changes.push(Box::new(IndexRenameConstraintChange {
+            constraint_name: constraint_name.to_string(),
+            new_constraint_name: new_constraint_name.to_string(),
+        }))
+    }
+
+    fn add_index(&mut self, columns: &[&str], idx_name: Option<&str>) {
+        let index = self.index(columns, idx_name);
+        self.statements.push(Box::new(index));
+    }
+
+    fn drop_index(&mut self, index_name: &str) {
+        self.drop_index_with(index_name, false, false);
+    }
+
+    fn drop_index_with(&mut self, index_name: &str, if_exists: bool, concurrently: bool) {
+        self.statements.push(Box::new(IndexDropChange {
+            table_name: Some(self.name.clone()),
+            index_name: index_name.to_string(),
+            if_exists,
+            concurrently,
+        }))
+    }
+}
+
+/// Access method of an index (`USING ...`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndexMethod {
+    BTree,
+    Hash,
+    Gist,
+    /// Generalized inverted index, e.g. for full-text search (`tsvector`) or
+    /// `jsonb` columns
+    Gin,
+    Brin,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IndexOptions {
+    pub(crate) unique: bool,
+    /// Access method, `None` is the dialect's default
+    pub(crate) method: Option<IndexMethod>,
+    /// Storage parameters (`WITH (...)`) as key/value pairs.
+    pub(crate) storage_parameters: Vec<(String, String)>,
+    pub(crate) tablespace: Option<String>,
+    /// Only create the index on the partitioned table itself, not on its
+    /// partitions (`ON ONLY`).
+    pub(crate) only: bool,
+    /// Condition of a partial index (`WHERE ...`), only rows matching it are
+    /// indexed.
+    pub(crate) where_clause: Option<Expr>,
+    /// Build the index without locking out writes (`CONCURRENTLY`).
+    pub(crate) concurrently: bool,
+    /// Skip creating the index, if an index with the same name exists.
+    pub(crate) if_not_exists: bool,
+    /// Non-key columns, which are stored in the index to allow index-only
+    /// scans (`INCLUDE (...)`).
+    pub(crate) include: Vec<String>,
+}
+
+impl IndexOptions {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+}
+
+/// Sort order of an index column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Position of `NULL` values within an index column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+/// Column of an index together with its sort order and operator class. Plain
+/// column names convert into an [IndexColumn] with the dialect's defaults,
+/// while a raw [Ident] is rendered as is, e.g. for expression indexes.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "postgres")] {
+/// use sql_press::{
+///     change::ChangeSet,
+///     ident::Ident,
+///     index::{index, IndexColumn},
+///     sql_dialect::Postgres,
+/// };
+///
+/// let mut cs = ChangeSet::new();
+/// cs.create_index(
+///     index(
+///         "orders",
+///         vec![IndexColumn::new("user_id"), IndexColumn::desc("created_at").nulls_last()],
+///     )
+///     .build(),
+/// );
+///
+/// assert_eq!(
+///     r#"CREATE INDEX ON "public"."orders" ("user_id", "created_at" DESC NULLS LAST);"#,
+///     cs.get_ddl(Postgres::new_rc()).unwrap()
+/// );
+///
+/// let mut cs = ChangeSet::new();
+/// cs.create_index(
+///     index("users", vec![Ident::raw("(lower(email))")])
+///         .name("users_email_lower_idx")
+///         .build(),
+/// );
+///
+/// assert_eq!(
+///     r#"CREATE INDEX "users_email_lower_idx" ON "public"."users" ((lower(email)));"#,
+///     cs.get_ddl(Postgres::new_rc()).unwrap()
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexColumn {
+    pub(crate) name: Ident,
+    pub(crate) order: Option<SortOrder>,
+    pub(crate) nulls: Option<NullsOrder>,
+    /// Operator class of the column, e.g. `gin_trgm_ops` for trigram indexes
+    pub(crate) opclass: Option<String>,
+}
+
+impl IndexColumn {
+    pub fn new<N: Into<Ident>>(name: N) -> Self {
+        Self {
+            name: name.into(),
+            order: None,
+            nulls: None,
+            opclass: None,
+        }
+    }
+
+    pub fn asc<N: Into<Ident>>(name: N) -> Self {
+        Self {
+            order: Some(SortOrder::Asc),
+            ..Self::new(name)
+        }
+    }
+
+    pub fn desc<N: Into<Ident>>(name: N) -> Self {
+        Self {
+            order: Some(SortOrder::Desc),
+            ..Self::new(name)
+        }
+    }
+
+    pub fn nulls_first(mut self) -> Self {
+        self.nulls = Some(NullsOrder::First);
+
+        self
+    }
+
+    pub fn nulls_last(mut self) -> Self {
+        self.nulls = Some(NullsOrder::Last);
+
+        self
+    }
+
+    /// Operator class, which is used for the column, e.g.
+    /// `varchar_pattern_ops` to support `LIKE` queries.
+    pub fn opclass(mut self, opclass: &str) -> Self {
+        self.opclass = Some(opclass.into());
+
+        self
+    }
+}
+
+impl From<&str> for IndexColumn {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for IndexColumn {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<Ident> for IndexColumn {
+    fn from(name: Ident) -> Self {
+        Self::new(name)
+    }
 }
 
 #[derive(Debug)]
 pub struct IndexAddCombinedChange {
-    table_name: String,
-    columns: Vec<String>,
-    idx_name: Option<String>,
+    pub(crate) table_name: String,
+    pub(crate) columns: Vec<IndexColumn>,
+    pub(crate) idx_name: Option<String>,
+    pub(crate) options: IndexOptions,
 }
 
-#[derive(Debug)]
+impl IndexAddCombinedChange {
+    pub fn new<C: Into<IndexColumn>>(table_name: &str, columns: Vec<C>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            columns: columns.into_iter().map(Into::into).collect(),
+            idx_name: None,
+            options: IndexOptions::new(),
+        }
+    }
+
+    /// Names of the indexed columns.
+    pub(crate) fn column_names(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|c| c.name.as_str().to_string())
+            .collect()
+    }
+}
+
+pub struct IndexBuilder {
+    inner: IndexAddCombinedChange,
+}
+
+impl IndexBuilder {
+    pub fn new<C: Into<IndexColumn>>(table_name: &str, columns: Vec<C>) -> Self {
+        Self {
+            inner: IndexAddCombinedChange::new(table_name, columns),
+        }
+    }
+
+    /// Name of the index.
+    pub fn name(mut self, idx_name: &str) -> Self {
+        self.inner.idx_name = Some(idx_name.into());
+
+        self
+    }
+
+    pub fn unique(mut self, unique: bool) -> Self {
+        self.inner.options.unique = unique;
+
+        self
+    }
+
+    /// Access method of the index, e.g. [IndexMethod::Gin] for full-text
+    /// search.
+    pub fn using(mut self, method: IndexMethod) -> Self {
+        self.inner.options.method = Some(method);
+
+        self
+    }
+
+    /// Adds a storage parameter, rendered as `WITH (name = value)`.
+    pub fn storage_parameter(mut self, name: &str, value: &str) -> Self {
+        self.inner
+            .options
+            .storage_parameters
+            .push((name.into(), value.into()));
+
+        self
+    }
+
+    /// Convenience method for the `fillfactor` storage parameter.
+    pub fn fillfactor(self, fillfactor: u8) -> Self {
+        self.storage_parameter("fillfactor", &fillfactor.to_string())
+    }
+
+    pub fn tablespace(mut self, tablespace: &str) -> Self {
+        self.inner.options.tablespace = Some(tablespace.into());
+
+        self
+    }
+
+    /// Creates the index only on the partitioned parent table. Matching
+    /// indexes of the partitions have to be attached via
+    /// [ChangeSet::attach_index_partition][crate::change::ChangeSet::attach_index_partition]
+    /// afterwards.
+    pub fn only(mut self, only: bool) -> Self {
+        self.inner.options.only = only;
+
+        self
+    }
+
+    /// Creates a partial index, which only covers the rows matching the
+    /// `condition`, e.g. a unique index on all rows, which are not soft
+    /// deleted.
+    pub fn where_clause(mut self, condition: Expr) -> Self {
+        self.inner.options.where_clause = Some(condition);
+
+        self
+    }
+
+    /// Stores the given non-key columns in the index (covering index), so
+    /// queries selecting them can be answered by an index-only scan.
+    pub fn include(mut self, columns: &[&str]) -> Self {
+        self.inner
+            .options
+            .include
+            .extend(columns.iter().map(|c| c.to_string()));
+
+        self
+    }
+
+    /// Builds the index without locking out writes on the table. The index
+    /// can't be created inside a transaction then (see
+    /// [Change::is_transactional]).
+    pub fn concurrently(mut self, concurrently: bool) -> Self {
+        self.inner.options.concurrently = concurrently;
+
+        self
+    }
+
+    /// Renders `IF NOT EXISTS`, so the index is only created if no index with
+    /// the same name exists. Requires a [name][IndexBuilder::name].
+    pub fn if_not_exists(mut self, if_not_exists: bool) -> Self {
+        self.inner.options.if_not_exists = if_not_exists;
+
+        self
+    }
+
+    pub fn build(self) -> IndexAddCombinedChange {
+        self.inner
+    }
+}
+
+/// Defines a new index on the `columns` of `table_name`, which can be created
+/// via [ChangeSet::create_index][crate::change::ChangeSet::create_index].
+///
+/// # Example
+/// ```
+/// use sql_press::{change::ChangeSet, index::index};
+///
+/// let mut cs = ChangeSet::new();
+/// cs.create_index(
+///     index("users", vec!["email"])
+///         .name("idx_users_email")
+///         .unique(true)
+///         .fillfactor(70)
+///         .tablespace("fast_ssd")
+///         .build(),
+/// );
+/// ```
+pub fn index<C: Into<IndexColumn>>(table_name: &str, columns: Vec<C>) -> IndexBuilder {
+    IndexBuilder::new(table_name, columns)
+}
+
+/// Match type of a foreign key, which defines how `NULL` values in composite
+/// foreign keys are treated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForeignKeyMatch {
+    Full,
+    /// Part of the SQL standard, but neither implemented by postgres nor
+    /// T-SQL
+    Partial,
+    Simple,
+}
+
+/// Action, which is performed on the referencing rows when the referenced
+/// row is deleted or updated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReferentialAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ForeignKeyOptions {
+    pub(crate) match_type: Option<ForeignKeyMatch>,
+    pub(crate) on_delete: Option<ReferentialAction>,
+    pub(crate) on_update: Option<ReferentialAction>,
+    /// Skips the validation of existing rows (see
+    /// [IndexAlter::validate_constraint])
+    pub(crate) not_valid: bool,
+}
+
+impl ForeignKeyOptions {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct IndexAddForeignChange {
-    column_name: String,
-    foreign_table_name: String,
-    foreign_column_name: String,
-    idx_name: Option<String>,
-    add_clause: bool,
+    pub(crate) columns: Vec<String>,
+    pub(crate) foreign_table_name: String,
+    pub(crate) foreign_columns: Vec<String>,
+    pub(crate) idx_name: Option<String>,
+    pub(crate) add_clause: bool,
+    pub(crate) options: ForeignKeyOptions,
+}
+
+impl IndexAddForeignChange {
+    pub fn new(column_name: &str, foreign_table_name: &str, foreign_column_name: &str) -> Self {
+        Self::new_composite(&[column_name], foreign_table_name, &[foreign_column_name])
+    }
+
+    /// Foreign key, which spans multiple columns (see
+    /// [IndexAdd::add_foreign_index_composite]).
+    pub fn new_composite(
+        columns: &[&str],
+        foreign_table_name: &str,
+        foreign_columns: &[&str],
+    ) -> Self {
+        assert!(
+            !columns.is_empty() && columns.len() == foreign_columns.len(),
+            "A foreign key needs the same number of referencing and referenced columns"
+        );
+        Self {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            foreign_table_name: foreign_table_name.into(),
+            foreign_columns: foreign_columns.iter().map(|c| c.to_string()).collect(),
+            idx_name: None,
+            add_clause: false,
+            options: ForeignKeyOptions::new(),
+        }
+    }
+}
+
+pub struct ForeignKeyBuilder {
+    inner: IndexAddForeignChange,
+}
+
+impl ForeignKeyBuilder {
+    pub fn new(column_name: &str, foreign_table_name: &str, foreign_column_name: &str) -> Self {
+        Self {
+            inner: IndexAddForeignChange::new(column_name, foreign_table_name, foreign_column_name),
+        }
+    }
+
+    /// Foreign key, which spans multiple columns (see
+    /// [IndexAdd::add_foreign_index_composite]).
+    pub fn composite(columns: &[&str], foreign_table_name: &str, foreign_columns: &[&str]) -> Self {
+        Self {
+            inner: IndexAddForeignChange::new_composite(
+                columns,
+                foreign_table_name,
+                foreign_columns,
+            ),
+        }
+    }
+
+    /// Name of the foreign key constraint.
+    pub fn name(mut self, idx_name: &str) -> Self {
+        self.inner.idx_name = Some(idx_name.into());
+
+        self
+    }
+
+    pub fn match_type(mut self, match_type: ForeignKeyMatch) -> Self {
+        self.inner.options.match_type = Some(match_type);
+
+        self
+    }
+
+    pub fn on_delete(mut self, action: ReferentialAction) -> Self {
+        self.inner.options.on_delete = Some(action);
+
+        self
+    }
+
+    pub fn on_update(mut self, action: ReferentialAction) -> Self {
+        self.inner.options.on_update = Some(action);
+
+        self
+    }
+
+    /// Adds the foreign key without validating the existing rows, which
+    /// avoids a long lock on large tables. The constraint is validated
+    /// afterwards with [IndexAlter::validate_constraint].
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "postgres")] {
+    /// use sql_press::{change::ChangeSet, index::foreign_key, sql_dialect::Postgres};
+    ///
+    /// let mut cs = ChangeSet::new();
+    /// cs.alter_table("order", |t| {
+    ///     t.add_foreign_key(
+    ///         foreign_key("user_id", "user", "id")
+    ///             .name("fk_order_user")
+    ///             .not_valid(true)
+    ///             .build(),
+    ///     );
+    /// });
+    /// cs.alter_table("order", |t| t.validate_constraint("fk_order_user"));
+    ///
+    /// assert_eq!(r#"ALTER TABLE "public"."order"
+    /// ADD CONSTRAINT "fk_order_user" FOREIGN KEY("user_id") REFERENCES "public"."user"("id") NOT VALID;
+    ///
+    /// ALTER TABLE "public"."order"
+    /// VALIDATE CONSTRAINT "fk_order_user";"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+    /// # }
+    /// ```
+    pub fn not_valid(mut self, not_valid: bool) -> Self {
+        self.inner.options.not_valid = not_valid;
+
+        self
+    }
+
+    pub fn build(self) -> IndexAddForeignChange {
+        self.inner
+    }
+}
+
+/// Defines a foreign key from `column_name` to `foreign_column_name` of
+/// `foreign_table_name`, which can be added via
+/// [IndexAdd::add_foreign_key] or [IndexAlter::add_foreign_key].
+///
+/// # Example
+/// ```
+/// use sql_press::{change::ChangeSet, index::{foreign_key, ForeignKeyMatch}};
+///
+/// let mut cs = ChangeSet::new();
+/// cs.alter_table("order", |t| {
+///     t.add_foreign_key(
+///         foreign_key("user_id", "user", "id")
+///             .name("fk_order_user")
+///             .match_type(ForeignKeyMatch::Full)
+///             .build(),
+///     );
+/// });
+/// ```
+pub fn foreign_key(
+    column_name: &str,
+    foreign_table_name: &str,
+    foreign_column_name: &str,
+) -> ForeignKeyBuilder {
+    ForeignKeyBuilder::new(column_name, foreign_table_name, foreign_column_name)
 }
 
 #[derive(Debug)]
 pub struct IndexAddPrimaryChange {
-    columns: Vec<String>,
+    pub(crate) constraint_name: Option<String>,
+    pub(crate) columns: Vec<String>,
+    pub(crate) add_clause: bool,
 }
 
 #[derive(Debug)]
 pub struct IndexAddUniqueChange {
-    constraint_name: String,
+    pub(crate) constraint_name: String,
+    pub(crate) columns: Vec<String>,
+    /// Treats `NULL` values as equal (`UNIQUE NULLS NOT DISTINCT`)
+    pub(crate) nulls_not_distinct: bool,
+    pub(crate) add_clause: bool,
+}
+
+impl IndexAddUniqueChange {
+    pub fn new(constraint_name: &str, columns: Vec<&str>) -> Self {
+        Self {
+            constraint_name: constraint_name.into(),
+            columns: columns.iter().map(|i| i.to_string()).collect(),
+            nulls_not_distinct: false,
+            add_clause: false,
+        }
+    }
+}
+
+pub struct UniqueConstraintBuilder {
+    inner: IndexAddUniqueChange,
+}
+
+impl UniqueConstraintBuilder {
+    pub fn new(constraint_name: &str, columns: Vec<&str>) -> Self {
+        Self {
+            inner: IndexAddUniqueChange::new(constraint_name, columns),
+        }
+    }
+
+    /// Treats `NULL` values as equal, so only a single row may contain
+    /// `NULL` in the constrained columns.
+    pub fn nulls_not_distinct(mut self, nulls_not_distinct: bool) -> Self {
+        self.inner.nulls_not_distinct = nulls_not_distinct;
+
+        self
+    }
+
+    pub fn build(self) -> IndexAddUniqueChange {
+        self.inner
+    }
+}
+
+/// Defines a unique constraint over the given columns, which can be added
+/// via [IndexAdd::add_unique] or [IndexAlter::add_unique].
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "postgres")] {
+/// use sql_press::{change::ChangeSet, index::unique_constraint, sql_dialect::Postgres};
+///
+/// let mut cs = ChangeSet::new();
+/// cs.alter_table("account", |t| {
+///     t.add_unique(
+///         unique_constraint("uq_account_provider", vec!["provider", "external_id"])
+///             .nulls_not_distinct(true)
+///             .build(),
+///     );
+/// });
+///
+/// assert_eq!(r#"ALTER TABLE "public"."account"
+/// ADD CONSTRAINT "uq_account_provider" UNIQUE NULLS NOT DISTINCT ("provider", "external_id");"#, cs.get_ddl(Postgres::new_rc()).unwrap());
+/// # }
+/// ```
+pub fn unique_constraint(constraint_name: &str, columns: Vec<&str>) -> UniqueConstraintBuilder {
+    UniqueConstraintBuilder::new(constraint_name, columns)
+}
+
+#[derive(Debug)]
+pub struct IndexAddCheckChange {
+    pub(crate) constraint_name: String,
+    pub(crate) condition: Expr,
+    pub(crate) add_clause: bool,
+    pub(crate) not_valid: bool,
+}
+
+/// Replaces the primary key of a table without holding an access exclusive
+/// lock while the new index is built.
+#[derive(Debug)]
+pub struct PrimaryKeyReplaceChange {
+    table_name: String,
     columns: Vec<String>,
+    /// Name of the existing primary key constraint
+    constraint_name: Option<String>,
+}
+
+impl PrimaryKeyReplaceChange {
+    pub fn new(table_name: &str, columns: Vec<&str>, constraint_name: Option<&str>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            columns: columns.iter().map(|i| i.to_string()).collect(),
+            constraint_name: constraint_name.map(|n| n.into()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexAddUsingIndexChange {
+    constraint_name: Option<String>,
+    index_name: String,
+    primary: bool,
+}
+
+/// Attaches the index of a partition to the index of its partitioned parent
+/// table.
+#[derive(Debug)]
+pub struct IndexAttachPartitionChange {
+    index_name: String,
+    partition_index_name: String,
+}
+
+impl IndexAttachPartitionChange {
+    pub fn new(index_name: &str, partition_index_name: &str) -> Self {
+        Self {
+            index_name: index_name.into(),
+            partition_index_name: partition_index_name.into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexRenameChange {
+    index_name: String,
+    new_index_name: String,
+}
+
+impl IndexRenameChange {
+    pub fn new(index_name: &str, new_index_name: &str) -> Self {
+        Self {
+            index_name: index_name.into(),
+            new_index_name: new_index_name.into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ReindexTarget {
+    Index { concurrently: bool },
+    Table,
+}
+
+/// Rebuilds a single index or all indexes of a table.
+#[derive(Debug)]
+pub struct ReindexChange {
+    name: String,
+    target: ReindexTarget,
+}
+
+impl ReindexChange {
+    pub fn new(name: &str, target: ReindexTarget) -> Self {
+        Self {
+            name: name.into(),
+            target,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexDropUniqueChange {
+    constraint_name: String,
+}
+
+#[derive(Debug)]
+pub struct IndexDropCheckChange {
+    constraint_name: String,
+}
+
+#[derive(Debug)]
+pub struct IndexValidateConstraintChange {
+    constraint_name: String,
+}
+
+#[derive(Debug)]
+pub struct IndexDropPrimaryChange {
+    table_name: String,
+    constraint_name: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct IndexRenameConstraintChange {
+    constraint_name: String,
+    new_constraint_name: String,
+}
+
+#[derive(Debug)]
+pub struct IndexDropConstraintChange {
+    constraint_name: String,
+    if_exists: bool,
+    cascade: bool,
+}
+
+#[derive(Debug)]
+pub struct IndexDropChange {
+    /// Table of the index, `None` if the index was dropped by name only
+    table_name: Option<String>,
+    index_name: String,
+    if_exists: bool,
+    concurrently: bool,
+}
+
+impl IndexDropChange {
+    pub fn new(index_name: &str, if_exists: bool, concurrently: bool) -> Self {
+        Self {
+            table_name: None,
+            index_name: index_name.into(),
+            if_exists,
+            concurrently,
+        }
+    }
 }
 
 impl Change for IndexAddPrimaryChange {
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
-        dialect.add_primary_index(&self.columns)
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.add_primary_index(
+            self.constraint_name.as_deref(),
+            &self.columns,
+            self.add_clause,
+        )
     }
 }
 
 impl Change for IndexAddCombinedChange {
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
-        dialect.add_index(&self.table_name, &self.columns, &self.idx_name)
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.add_index(
+            &self.table_name,
+            &self.columns,
+            &self.idx_name,
+            &self.options,
+        )
+    }
+
+    fn is_lock_heavy(&self) -> bool {
+        !self.options.concurrently
+    }
+
+    fn is_transactional(&self) -> bool {
+        !self.options.concurrently
     }
 }
 
 impl Change for IndexAddForeignChange {
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
         dialect.add_foreign_index(
-            &self.column_name,
+            &self.columns,
             &self.foreign_table_name,
-            &self.foreign_column_name,
+            &self.foreign_columns,
             self.idx_name.clone(),
             &self.add_clause,
+            &self.options,
         )
     }
 }
 
 impl Change for IndexAddUniqueChange {
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
-        dialect.add_unique_constraint(&self.constraint_name, &self.columns)
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.add_unique_constraint(
+            &self.constraint_name,
+            &self.columns,
+            self.nulls_not_distinct,
+            self.add_clause,
+        )
+    }
+}
+
+impl Change for IndexAddCheckChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.add_check_constraint(
+            &self.constraint_name,
+            &self.condition,
+            self.add_clause,
+            self.not_valid,
+        )
+    }
+}
+
+impl Change for IndexDropCheckChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.drop_check_constraint(&self.constraint_name)
+    }
+}
+
+impl Change for IndexAddUsingIndexChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        if self.primary {
+            dialect.add_primary_index_using_index(&self.index_name)
+        } else {
+            dialect.add_unique_constraint_using_index(
+                self.constraint_name.as_deref().unwrap_or_default(),
+                &self.index_name,
+            )
+        }
+    }
+}
+
+impl Change for PrimaryKeyReplaceChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.replace_primary_key_concurrently(
+            &self.table_name,
+            &self.columns,
+            self.constraint_name.as_deref(),
+        )
+    }
+
+    fn is_transactional(&self) -> bool {
+        false
+    }
+}
+
+impl Change for IndexDropUniqueChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.drop_unique_constraint(&self.constraint_name)
+    }
+}
+
+impl Change for IndexDropConstraintChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.drop_constraint(&self.constraint_name, self.if_exists, self.cascade)
+    }
+}
+
+impl Change for IndexValidateConstraintChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.validate_constraint(&self.constraint_name)
+    }
+}
+
+impl Change for IndexDropPrimaryChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.drop_primary_key(&self.table_name, self.constraint_name.as_deref())
+    }
+}
+
+impl Change for IndexRenameConstraintChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.rename_constraint(&self.constraint_name, &self.new_constraint_name)
+    }
+}
+
+impl Change for IndexDropChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.drop_index(
+            self.table_name.as_deref(),
+            &self.index_name,
+            self.if_exists,
+            self.concurrently,
+        )
+    }
+
+    fn is_transactional(&self) -> bool {
+        !self.concurrently
+    }
+}
+
+impl Change for ReindexChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        match self.target {
+            ReindexTarget::Index { concurrently } => {
+                dialect.reindex_index(&self.name, concurrently)
+            }
+            ReindexTarget::Table => dialect.reindex_table(&self.name),
+        }
+    }
+
+    fn is_lock_heavy(&self) -> bool {
+        !matches!(self.target, ReindexTarget::Index { concurrently: true })
+    }
+
+    fn is_transactional(&self) -> bool {
+        !matches!(self.target, ReindexTarget::Index { concurrently: true })
+    }
+}
+
+impl Change for IndexRenameChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.rename_index(&self.index_name, &self.new_index_name)
+    }
+}
+
+impl Change for IndexAttachPartitionChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.attach_index_partition(&self.index_name, &self.partition_index_name)
     }
 }
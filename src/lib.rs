@@ -4,11 +4,24 @@
 //! Those changes will be converted to DDL (effectively a plain
 //! [String][std::string::String]) with a supported SQL Dialect.
 //!
+//! # Features
+//!
+//! Every SQL dialect is gated behind its own cargo feature, so only the
+//! dialects actually in use are compiled:
+//!
+//! - `postgres` (enabled by default): [Postgres][crate::sql_dialect::Postgres]
+//! - `mssql`: `Mssql` (Microsoft SQL Server)
+//! - `cockroach`: `Cockroach` (CockroachDB, implies `postgres`)
+//!
+//! The `test-utils` feature exposes a conformance suite for custom dialects
+//! in `testing::conformance`.
+//!
 //! # Examples
 //!
 //! ## Create a new Table
 //!
 //! ```
+//! # #[cfg(feature = "postgres")] {
 //! use sql_press::{
 //!     change::ChangeSet,
 //!     column::{varchar, uuid},
@@ -26,12 +39,14 @@
 //! });
 //!
 //! let ddl = Postgres::new_rc();
-//! println!("{}", cs.get_ddl(ddl));
+//! println!("{}", cs.get_ddl(ddl).unwrap());
+//! # }
 //! ```
 //!
 //! ## Rename an existing Table
 //!
 //! ```
+//! # #[cfg(feature = "postgres")] {
 //! use sql_press::{
 //!     change::ChangeSet,
 //!     sql_dialect::Postgres,
@@ -42,12 +57,14 @@
 //! cs.rename_table("my_new_table", "my_actual_table");
 //!
 //! let ddl = Postgres::new_rc();
-//! println!("{}", cs.get_ddl(ddl));
+//! println!("{}", cs.get_ddl(ddl).unwrap());
+//! # }
 //! ```
 //!
 //! ## Alter (change) columns within an existing table
 //!
 //! ```
+//! # #[cfg(feature = "postgres")] {
 //! use sql_press::{
 //!     change::ChangeSet,
 //!     column::{varchar, ColumnType},
@@ -64,12 +81,14 @@
 //! });
 //!
 //! let ddl = Postgres::new_rc();
-//! println!("{}", cs.get_ddl(ddl));
+//! println!("{}", cs.get_ddl(ddl).unwrap());
+//! # }
 //! ```
 //!
 //! ## Delete / Drop a table
 //!
 //! ```
+//! # #[cfg(feature = "postgres")] {
 //! use sql_press::{
 //!     change::ChangeSet,
 //!     sql_dialect::Postgres,
@@ -80,11 +99,20 @@
 //! cs.drop_table("my_actual_table");
 //!
 //! let ddl = Postgres::new_rc();
-//! println!("{}", cs.get_ddl(ddl));
+//! println!("{}", cs.get_ddl(ddl).unwrap());
+//! # }
 //! ```
 
 pub mod change;
 pub mod column;
+pub mod error;
+pub mod expr;
+pub mod ident;
 pub mod index;
+pub mod naming;
+pub mod precondition;
 pub mod sql_dialect;
 pub mod table;
+pub mod testing;
+pub mod types;
+pub mod view;
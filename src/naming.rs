@@ -0,0 +1,63 @@
+//! Provides the [NamingStrategy], which generates the names of constraints
+//! and indexes, which were added without an explicit name (see
+//! [ChangeSet::naming_strategy][crate::change::ChangeSet::naming_strategy]).
+use std::fmt::Debug;
+
+/// Generates names for foreign keys, unique constraints and indexes. All
+/// functions get the name of the table and the columns covered by the
+/// constraint or index.
+pub trait NamingStrategy: Debug {
+    fn foreign_key(&self, table_name: &str, columns: &[String]) -> String;
+
+    fn unique(&self, table_name: &str, columns: &[String]) -> String;
+
+    fn index(&self, table_name: &str, columns: &[String]) -> String;
+}
+
+/// Names constraints and indexes like `fk_<table>_<columns>`,
+/// `uq_<table>_<columns>` and `idx_<table>_<columns>`, where multiple
+/// columns are joined by `_`.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultNamingStrategy;
+
+impl DefaultNamingStrategy {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl NamingStrategy for DefaultNamingStrategy {
+    fn foreign_key(&self, table_name: &str, columns: &[String]) -> String {
+        format!("fk_{}_{}", table_name, columns.join("_"))
+    }
+
+    fn unique(&self, table_name: &str, columns: &[String]) -> String {
+        format!("uq_{}_{}", table_name, columns.join("_"))
+    }
+
+    fn index(&self, table_name: &str, columns: &[String]) -> String {
+        format!("idx_{}_{}", table_name, columns.join("_"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_naming_strategy() {
+        let n = DefaultNamingStrategy::new();
+        assert_eq!(
+            n.foreign_key("order", &["user_id".into()]),
+            "fk_order_user_id"
+        );
+        assert_eq!(
+            n.unique("order", &["user_id".into(), "number".into()]),
+            "uq_order_user_id_number"
+        );
+        assert_eq!(
+            n.index("order", &["created_at".into()]),
+            "idx_order_created_at"
+        );
+    }
+}
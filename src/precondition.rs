@@ -0,0 +1,61 @@
+//! Provides conditional execution of changes depending on the state of the
+//! database catalog.
+use std::rc::Rc;
+
+use crate::{
+    change::{Change, ChangeSet},
+    error::{Result, SqlPressError},
+    sql_dialect::SqlDialect,
+};
+
+/// Condition on the database catalog, which is checked before the guarded
+/// changes are executed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Precondition {
+    TableExists(String),
+    TableMissing(String),
+    /// Table and column name
+    ColumnExists(String, String),
+    /// Table and column name
+    ColumnMissing(String, String),
+}
+
+/// Changes, which are only executed if the [Precondition] holds.
+///
+/// The check and the guarded changes are rendered as a single statement
+/// block, so changes which can't run inside a transaction (e.g. building an
+/// index `CONCURRENTLY`) can't be guarded by a precondition.
+#[derive(Debug)]
+pub struct ConditionalChange {
+    precondition: Precondition,
+    changes: ChangeSet,
+}
+
+impl ConditionalChange {
+    pub fn new(precondition: Precondition, changes: ChangeSet) -> Self {
+        Self {
+            precondition,
+            changes,
+        }
+    }
+
+    pub(crate) fn changes(&self) -> &ChangeSet {
+        &self.changes
+    }
+}
+
+impl Change for ConditionalChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        if !self.changes.is_transactional() {
+            return Err(SqlPressError::invalid(
+                "changes which can't run inside a transaction can't be guarded by a precondition",
+            ));
+        }
+
+        dialect.only_if(&self.precondition, &self.changes.get_ddl(dialect.clone())?)
+    }
+
+    fn is_transactional(&self) -> bool {
+        self.changes.is_transactional()
+    }
+}
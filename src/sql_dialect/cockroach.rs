@@ -0,0 +1,688 @@
+//! CockroachDB implementation of the [SqlDialect] trait. CockroachDB speaks
+//! the postgres wire protocol and most of its DDL, so this dialect delegates
+//! to [Postgres] and only overrides the statements, where CockroachDB
+//! differs:
+//!
+//! - tables, columns and indexes are created with `IF NOT EXISTS`
+//! - indexes are addressed as `table@index`
+//! - primary keys are replaced with `ALTER PRIMARY KEY`
+//! - `REINDEX`, tablespaces, index and table partitions and `USING INDEX`
+//!   constraints are not supported
+//!
+//! Note, that CockroachDB can't change the type of a column (`ALTER COLUMN
+//! ... TYPE`) within an explicit transaction, so such changes have to be
+//! executed on their own.
+use std::{rc::Rc, time::Duration};
+
+use crate::{
+    column::{ColumnType, Constraints, Storage},
+    error::{Result, SqlPressError},
+    expr::{Expr, Value},
+    ident::Ident,
+    index::{ForeignKeyOptions, IndexColumn, IndexMethod, IndexOptions, NullsOrder, SortOrder},
+    precondition::Precondition,
+    table::PartitionBound,
+    types::EnumValuePosition,
+};
+
+use super::{Postgres, SqlDialect, TypeMapping};
+
+#[derive(Debug, Clone, Default)]
+pub struct Cockroach {
+    inner: Postgres,
+}
+
+impl Cockroach {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Convenience method to directly return a [std::rc::Rc] of this struct.
+    pub fn new_rc() -> Rc<Self> {
+        Rc::new(Self::new())
+    }
+
+    /// Qualifies all objects with the given schema instead of the default one.
+    pub fn with_schema(mut self, schema: &str) -> Self {
+        self.inner = self.inner.with_schema(schema);
+
+        self
+    }
+
+    /// Overrides the built-in [ColumnType] mapping of this dialect with the
+    /// given [TypeMapping].
+    pub fn with_type_mapping<M: TypeMapping + 'static>(mut self, mapping: M) -> Self {
+        self.inner = self.inner.with_type_mapping(mapping);
+
+        self
+    }
+
+    /// Overrides the built-in [ColumnType] mapping with the given closure,
+    /// which returns `None` to fall back to the built-in mapping (see
+    /// [TypeMapping]).
+    pub fn with_type_override<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&ColumnType) -> Option<String> + 'static,
+    {
+        self.inner = self.inner.with_type_override(f);
+
+        self
+    }
+}
+
+/// Uses the given [Postgres] dialect with all of its options as base.
+impl From<Postgres> for Cockroach {
+    fn from(inner: Postgres) -> Self {
+        Self { inner }
+    }
+}
+
+impl Cockroach {
+    fn quote(&self, name: &str) -> Result<String> {
+        self.quote_ident(&Ident::new(name))
+    }
+
+    /// Quotes the given name and qualifies it with the schema.
+    fn qualified(&self, name: &str) -> Result<String> {
+        Ok(format!(
+            "{}.{}",
+            self.quote(&self.inner.schema)?,
+            self.quote(name)?
+        ))
+    }
+
+    fn quote_all(&self, names: &[String]) -> Result<String> {
+        Ok(names
+            .iter()
+            .map(|n| self.quote(n))
+            .collect::<Result<Vec<String>>>()?
+            .join(", "))
+    }
+
+    /// Quotes the columns of an index together with their sort order.
+    fn index_columns(&self, columns: &[IndexColumn]) -> Result<String> {
+        Ok(columns
+            .iter()
+            .map(|c| {
+                Ok(format!(
+                    "{}{}{}{}",
+                    self.quote_ident(&c.name)?,
+                    c.opclass
+                        .as_ref()
+                        .map(|o| format!(" {}", o))
+                        .unwrap_or_default(),
+                    match c.order {
+                        None => "",
+                        Some(SortOrder::Asc) => " ASC",
+                        Some(SortOrder::Desc) => " DESC",
+                    },
+                    match c.nulls {
+                        None => "",
+                        Some(NullsOrder::First) => " NULLS FIRST",
+                        Some(NullsOrder::Last) => " NULLS LAST",
+                    }
+                ))
+            })
+            .collect::<Result<Vec<String>>>()?
+            .join(", "))
+    }
+}
+
+impl SqlDialect for Cockroach {
+    fn name(&self) -> &'static str {
+        "cockroach"
+    }
+
+    fn for_schema(&self, schema: &str) -> Rc<dyn SqlDialect> {
+        Rc::new(self.clone().with_schema(schema))
+    }
+
+    fn quote_ident(&self, ident: &Ident) -> Result<String> {
+        self.inner.quote_ident(ident)
+    }
+
+    fn create_table(
+        &self,
+        name: &str,
+        changes: Vec<String>,
+        _if_not_exists: bool,
+    ) -> Result<String> {
+        self.inner.create_table(name, changes, true)
+    }
+
+    fn alter_table(&self, name: &str, changes: Vec<String>, if_exists: bool) -> Result<String> {
+        self.inner.alter_table(name, changes, if_exists)
+    }
+
+    fn rename_table(&self, name: &str, new_table_name: &str) -> Result<String> {
+        self.inner.rename_table(name, new_table_name)
+    }
+
+    fn drop_table(&self, name: &str, if_exists: bool, cascade: bool) -> Result<String> {
+        self.inner.drop_table(name, if_exists, cascade)
+    }
+
+    fn drop_tables(&self, names: &[String], cascade: bool) -> Result<String> {
+        self.inner.drop_tables(names, cascade)
+    }
+
+    fn attach_partition(
+        &self,
+        _table_name: &str,
+        _partition_name: &str,
+        _bound: &PartitionBound,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support attaching partitions",
+        ))
+    }
+
+    fn detach_partition(
+        &self,
+        _table_name: &str,
+        _partition_name: &str,
+        _concurrently: bool,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support detaching partitions",
+        ))
+    }
+
+    fn add_column(
+        &self,
+        name: &str,
+        with_prefix: bool,
+        ct: &ColumnType,
+        constraints: &Constraints,
+    ) -> Result<String> {
+        Ok(format!(
+            "{}{}",
+            if with_prefix {
+                "ADD COLUMN IF NOT EXISTS "
+            } else {
+                ""
+            },
+            self.inner.add_column(name, false, ct, constraints)?
+        ))
+    }
+
+    fn rename_column(
+        &self,
+        table_name: &str,
+        name: &str,
+        new_name: &str,
+        if_exists: bool,
+    ) -> Result<String> {
+        self.inner
+            .rename_column(table_name, name, new_name, if_exists)
+    }
+
+    fn alter_column(
+        &self,
+        name: &str,
+        ct: &ColumnType,
+        conversion_method: Option<&str>,
+    ) -> Result<String> {
+        self.inner.alter_column(name, ct, conversion_method)
+    }
+
+    fn drop_column(&self, name: &str, if_exists: bool) -> Result<String> {
+        self.inner.drop_column(name, if_exists)
+    }
+
+    fn set_column_storage(&self, _name: &str, _storage: &Storage) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support column storage strategies",
+        ))
+    }
+
+    fn set_column_compression(&self, _name: &str, _compression: &str) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support column compression",
+        ))
+    }
+
+    fn add_index(
+        &self,
+        table_name: &str,
+        columns: &[IndexColumn],
+        idx_name: &Option<String>,
+        options: &IndexOptions,
+    ) -> Result<String> {
+        if options.tablespace.is_some() {
+            return Err(SqlPressError::unsupported(
+                "CockroachDB does not support tablespaces",
+            ));
+        }
+        if options.only {
+            return Err(SqlPressError::unsupported(
+                "CockroachDB does not support indexes on partitioned tables (ON ONLY)",
+            ));
+        }
+
+        Ok(format!(
+            "CREATE {}INDEX {}{}ON {}{} ({}){}{}{};",
+            if options.unique { "UNIQUE " } else { "" },
+            if options.concurrently {
+                "CONCURRENTLY "
+            } else {
+                ""
+            },
+            match idx_name {
+                Some(x) => format!("IF NOT EXISTS {} ", self.quote(x)?),
+                None => "".into(),
+            },
+            self.qualified(table_name)?,
+            match options.method {
+                None | Some(IndexMethod::BTree) => "",
+                Some(IndexMethod::Gin) => " USING GIN",
+                Some(m) => {
+                    return Err(SqlPressError::unsupported(format!(
+                        "CockroachDB does not support {:?} indexes",
+                        m
+                    )));
+                }
+            },
+            self.index_columns(columns)?,
+            if options.include.is_empty() {
+                "".into()
+            } else {
+                format!(" STORING ({})", self.quote_all(&options.include)?)
+            },
+            if options.storage_parameters.is_empty() {
+                "".into()
+            } else {
+                format!(
+                    " WITH ({})",
+                    options
+                        .storage_parameters
+                        .iter()
+                        .map(|(k, v)| format!("{} = {}", k, v))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            },
+            match &options.where_clause {
+                Some(c) => format!(" WHERE {}", self.expr(c)?),
+                None => "".into(),
+            }
+        ))
+    }
+
+    fn add_foreign_index(
+        &self,
+        columns: &[String],
+        foreign_table_name: &str,
+        foreign_columns: &[String],
+        idx_name: Option<String>,
+        add_clause: &bool,
+        options: &ForeignKeyOptions,
+    ) -> Result<String> {
+        self.inner.add_foreign_index(
+            columns,
+            foreign_table_name,
+            foreign_columns,
+            idx_name,
+            add_clause,
+            options,
+        )
+    }
+
+    fn add_primary_index(
+        &self,
+        constraint_name: Option<&str>,
+        columns: &[String],
+        add_clause: bool,
+    ) -> Result<String> {
+        self.inner
+            .add_primary_index(constraint_name, columns, add_clause)
+    }
+
+    fn add_unique_constraint(
+        &self,
+        constraint_name: &str,
+        columns: &[String],
+        nulls_not_distinct: bool,
+        add_clause: bool,
+    ) -> Result<String> {
+        if nulls_not_distinct {
+            return Err(SqlPressError::unsupported(
+                "CockroachDB does not support UNIQUE NULLS NOT DISTINCT",
+            ));
+        }
+        self.inner
+            .add_unique_constraint(constraint_name, columns, false, add_clause)
+    }
+
+    fn add_unique_constraint_using_index(
+        &self,
+        _constraint_name: &str,
+        _index_name: &str,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported("CockroachDB does not support constraints USING INDEX, a unique index already acts as unique constraint"))
+    }
+
+    fn add_primary_index_using_index(&self, _index_name: &str) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support PRIMARY KEY USING INDEX, use ALTER PRIMARY KEY instead",
+        ))
+    }
+
+    fn drop_unique_constraint(&self, constraint_name: &str) -> Result<String> {
+        self.inner.drop_unique_constraint(constraint_name)
+    }
+
+    fn add_check_constraint(
+        &self,
+        constraint_name: &str,
+        condition: &Expr,
+        add_clause: bool,
+        not_valid: bool,
+    ) -> Result<String> {
+        self.inner
+            .add_check_constraint(constraint_name, condition, add_clause, not_valid)
+    }
+
+    fn drop_check_constraint(&self, constraint_name: &str) -> Result<String> {
+        self.inner.drop_check_constraint(constraint_name)
+    }
+
+    fn drop_constraint(
+        &self,
+        constraint_name: &str,
+        if_exists: bool,
+        cascade: bool,
+    ) -> Result<String> {
+        self.inner
+            .drop_constraint(constraint_name, if_exists, cascade)
+    }
+
+    fn validate_constraint(&self, constraint_name: &str) -> Result<String> {
+        self.inner.validate_constraint(constraint_name)
+    }
+
+    fn drop_primary_key(&self, table_name: &str, constraint_name: Option<&str>) -> Result<String> {
+        self.inner.drop_primary_key(table_name, constraint_name)
+    }
+
+    fn rename_constraint(
+        &self,
+        constraint_name: &str,
+        new_constraint_name: &str,
+    ) -> Result<String> {
+        self.inner
+            .rename_constraint(constraint_name, new_constraint_name)
+    }
+
+    fn drop_index(
+        &self,
+        table_name: Option<&str>,
+        index_name: &str,
+        _if_exists: bool,
+        concurrently: bool,
+    ) -> Result<String> {
+        let index = match table_name {
+            Some(table_name) => {
+                format!(
+                    "{}@{}",
+                    self.qualified(table_name)?,
+                    self.quote(index_name)?
+                )
+            }
+            None => self.qualified(index_name)?,
+        };
+        Ok(format!(
+            "DROP INDEX {}IF EXISTS {};",
+            if concurrently { "CONCURRENTLY " } else { "" },
+            index
+        ))
+    }
+
+    fn rename_index(&self, index_name: &str, new_index_name: &str) -> Result<String> {
+        self.inner.rename_index(index_name, new_index_name)
+    }
+
+    fn attach_index_partition(
+        &self,
+        _index_name: &str,
+        _partition_index_name: &str,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support attaching index partitions",
+        ))
+    }
+
+    fn reindex_index(&self, _index_name: &str, _concurrently: bool) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support REINDEX",
+        ))
+    }
+
+    fn reindex_table(&self, _table_name: &str) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support REINDEX",
+        ))
+    }
+
+    /// CockroachDB rebuilds the primary index online with `ALTER PRIMARY KEY`,
+    /// which keeps the name of the primary key constraint.
+    fn replace_primary_key_concurrently(
+        &self,
+        table_name: &str,
+        columns: &[String],
+        _constraint_name: Option<&str>,
+    ) -> Result<String> {
+        Ok(format!(
+            "ALTER TABLE {} ALTER PRIMARY KEY USING COLUMNS ({});",
+            self.qualified(table_name)?,
+            self.quote_all(columns)?
+        ))
+    }
+
+    fn rename_view(&self, name: &str, new_view_name: &str) -> Result<String> {
+        self.inner.rename_view(name, new_view_name)
+    }
+
+    fn alter_view_owner(&self, name: &str, role: &str) -> Result<String> {
+        self.inner.alter_view_owner(name, role)
+    }
+
+    fn alter_view_set_options(&self, name: &str, options: &[(String, String)]) -> Result<String> {
+        self.inner.alter_view_set_options(name, options)
+    }
+
+    fn create_extension(&self, name: &str) -> Result<String> {
+        self.inner.create_extension(name)
+    }
+
+    fn ensure_uuid_generation(&self) -> Result<String> {
+        self.inner.ensure_uuid_generation()
+    }
+
+    fn insert_rows(
+        &self,
+        table_name: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+    ) -> Result<String> {
+        self.inner.insert_rows(table_name, columns, rows)
+    }
+
+    fn lock_timeout(&self, timeout: Duration) -> Result<String> {
+        self.inner.lock_timeout(timeout)
+    }
+
+    fn reset_lock_timeout(&self) -> Result<String> {
+        self.inner.reset_lock_timeout()
+    }
+
+    fn version_trigger(&self, table_name: &str) -> Result<String> {
+        self.inner.version_trigger(table_name)
+    }
+
+    fn audit_history(&self, table_name: &str, columns: &[(String, ColumnType)]) -> Result<String> {
+        self.inner.audit_history(table_name, columns)
+    }
+
+    fn only_if(&self, precondition: &Precondition, ddl: &str) -> Result<String> {
+        self.inner.only_if(precondition, ddl)
+    }
+
+    fn create_composite_type(&self, name: &str, fields: &[(String, ColumnType)]) -> Result<String> {
+        self.inner.create_composite_type(name, fields)
+    }
+
+    fn create_enum_type(&self, name: &str, values: &[String]) -> Result<String> {
+        self.inner.create_enum_type(name, values)
+    }
+
+    fn create_domain(
+        &self,
+        _name: &str,
+        _ct: &ColumnType,
+        _not_null: bool,
+        _checks: &[String],
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support domains",
+        ))
+    }
+
+    fn drop_domain(&self, _name: &str) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "CockroachDB does not support domains",
+        ))
+    }
+
+    fn add_enum_value(
+        &self,
+        name: &str,
+        value: &str,
+        position: &Option<EnumValuePosition>,
+    ) -> Result<String> {
+        self.inner.add_enum_value(name, value, position)
+    }
+
+    fn rename_type(&self, name: &str, new_type_name: &str) -> Result<String> {
+        self.inner.rename_type(name, new_type_name)
+    }
+
+    fn drop_type(&self, name: &str) -> Result<String> {
+        self.inner.drop_type(name)
+    }
+
+    fn expr(&self, expr: &Expr) -> Result<String> {
+        self.inner.expr(expr)
+    }
+
+    fn column_type(&self, ct: &ColumnType) -> Result<String> {
+        self.inner.column_type(ct)
+    }
+
+    fn constraints(&self, constraints: &Constraints) -> Result<String> {
+        if constraints.nulls_not_distinct {
+            return Err(SqlPressError::unsupported(
+                "CockroachDB does not support UNIQUE NULLS NOT DISTINCT",
+            ));
+        }
+        self.inner.constraints(constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_table() {
+        let d = Cockroach::new();
+        let ddl = d
+            .create_table(
+                "tag",
+                vec![d
+                    .add_column("id", false, &ColumnType::UUID, &Constraints::new())
+                    .unwrap()],
+                false,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE TABLE IF NOT EXISTS \"public\".\"tag\" (\n\"id\" uuid\n);"
+        );
+
+        let ddl = d
+            .alter_table(
+                "tag",
+                vec![d
+                    .add_column("name", true, &ColumnType::TEXT, &Constraints::new())
+                    .unwrap()],
+                false,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "ALTER TABLE \"public\".\"tag\"\nADD COLUMN IF NOT EXISTS \"name\" text;"
+        );
+    }
+
+    #[test]
+    fn quotes_schema() {
+        let d = Cockroach::from(Postgres::new().with_schema("tenant-A"));
+        assert_eq!(
+            d.drop_table("tag", false, false).unwrap(),
+            "DROP TABLE \"tenant-A\".\"tag\";"
+        );
+    }
+
+    #[test]
+    fn indexes() {
+        let d = Cockroach::from(Postgres::new().with_schema("app"));
+        let ddl = d
+            .add_index(
+                "tag",
+                &["name".into()],
+                &Some("tag_name_idx".into()),
+                &IndexOptions::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX IF NOT EXISTS \"tag_name_idx\" ON \"app\".\"tag\" (\"name\");"
+        );
+        let mut options = IndexOptions::new();
+        options.include = vec!["color".into()];
+        let ddl = d
+            .add_index("tag", &["name".into()], &None, &options)
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX ON \"app\".\"tag\" (\"name\") STORING (\"color\");"
+        );
+        assert_eq!(
+            d.drop_index(Some("tag"), "tag_name_idx", false, false)
+                .unwrap(),
+            "DROP INDEX IF EXISTS \"app\".\"tag\"@\"tag_name_idx\";"
+        );
+        assert_eq!(
+            d.drop_index(None, "tag_name_idx", false, true).unwrap(),
+            "DROP INDEX CONCURRENTLY IF EXISTS \"app\".\"tag_name_idx\";"
+        );
+        assert_eq!(
+            d.replace_primary_key_concurrently("tag", &["id".into(), "name".into()], None)
+                .unwrap(),
+            "ALTER TABLE \"app\".\"tag\" ALTER PRIMARY KEY USING COLUMNS (\"id\", \"name\");"
+        );
+    }
+
+    #[test]
+    fn reindex() {
+        assert_eq!(
+            Cockroach::new().reindex_table("tag"),
+            Err(SqlPressError::UnsupportedByDialect(
+                "CockroachDB does not support REINDEX".into()
+            ))
+        );
+    }
+}
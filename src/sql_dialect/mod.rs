@@ -1,17 +1,66 @@
 //! Central trait definition for what an [SqlDialect] implementation has to support.
-use crate::column::{ColumnType, Constraints};
+use std::{fmt::Debug, rc::Rc, time::Duration};
 
+use crate::{
+    column::{ColumnType, Constraints, Storage},
+    error::Result,
+    expr::{Expr, Value},
+    ident::Ident,
+    index::{ForeignKeyOptions, IndexColumn, IndexOptions},
+    precondition::Precondition,
+    table::PartitionBound,
+    types::EnumValuePosition,
+};
+
+#[cfg(feature = "cockroach")]
+pub mod cockroach;
+#[cfg(feature = "mssql")]
+pub mod mssql;
+#[cfg(feature = "postgres")]
 pub mod postgres;
+#[cfg(feature = "cockroach")]
+pub use cockroach::Cockroach;
+#[cfg(feature = "mssql")]
+pub use mssql::Mssql;
+#[cfg(feature = "postgres")]
 pub use postgres::Postgres;
 
 pub trait SqlDialect {
-    fn create_table(&self, name: &str, changes: Vec<String>, if_not_exists: bool) -> String;
+    /// Name of the dialect, which identifies it e.g. in a
+    /// [PortabilityReport][crate::change::PortabilityReport].
+    fn name(&self) -> &'static str;
+
+    /// Returns a copy of this dialect, which qualifies all objects with the
+    /// given schema.
+    fn for_schema(&self, schema: &str) -> Rc<dyn SqlDialect>;
+
+    /// Quotes the given identifier, unless it is raw (see [Ident::raw]).
+    fn quote_ident(&self, ident: &Ident) -> Result<String>;
 
-    fn alter_table(&self, name: &str, changes: Vec<String>) -> String;
+    fn create_table(&self, name: &str, changes: Vec<String>, if_not_exists: bool)
+        -> Result<String>;
 
-    fn rename_table(&self, name: &str, new_table_name: &str) -> String;
+    fn alter_table(&self, name: &str, changes: Vec<String>, if_exists: bool) -> Result<String>;
 
-    fn drop_table(&self, name: &str) -> String;
+    fn rename_table(&self, name: &str, new_table_name: &str) -> Result<String>;
+
+    fn drop_table(&self, name: &str, if_exists: bool, cascade: bool) -> Result<String>;
+
+    fn drop_tables(&self, names: &[String], cascade: bool) -> Result<String>;
+
+    fn attach_partition(
+        &self,
+        table_name: &str,
+        partition_name: &str,
+        bound: &PartitionBound,
+    ) -> Result<String>;
+
+    fn detach_partition(
+        &self,
+        table_name: &str,
+        partition_name: &str,
+        concurrently: bool,
+    ) -> Result<String>;
 
     fn add_column(
         &self,
@@ -19,30 +68,294 @@ pub trait SqlDialect {
         with_prefix: bool,
         ct: &ColumnType,
         constraints: &Constraints,
-    ) -> String;
+    ) -> Result<String>;
 
-    fn rename_column(&self, name: &str, new_name: &str) -> String;
+    /// Renders a standalone statement, since renaming a column can't be
+    /// combined with other changes of the table.
+    fn rename_column(
+        &self,
+        table_name: &str,
+        name: &str,
+        new_name: &str,
+        if_exists: bool,
+    ) -> Result<String>;
 
-    fn alter_column(&self, name: &str, ct: &ColumnType, conversion_method: Option<&str>) -> String;
+    fn alter_column(
+        &self,
+        name: &str,
+        ct: &ColumnType,
+        conversion_method: Option<&str>,
+    ) -> Result<String>;
+
+    fn drop_column(&self, name: &str, if_exists: bool) -> Result<String>;
+
+    fn set_column_storage(&self, name: &str, storage: &Storage) -> Result<String>;
 
-    fn drop_column(&self, name: &str, if_exists: bool) -> String;
+    fn set_column_compression(&self, name: &str, compression: &str) -> Result<String>;
 
-    fn add_index(&self, table_name: &str, columns: &[String], idx_name: &Option<String>) -> String;
+    fn add_index(
+        &self,
+        table_name: &str,
+        columns: &[IndexColumn],
+        idx_name: &Option<String>,
+        options: &IndexOptions,
+    ) -> Result<String>;
 
     fn add_foreign_index(
         &self,
-        column_name: &str,
+        columns: &[String],
         foreign_table_name: &str,
-        foreign_column_name: &str,
+        foreign_columns: &[String],
         idx_name: Option<String>,
         add_clause: &bool,
-    ) -> String;
+        options: &ForeignKeyOptions,
+    ) -> Result<String>;
+
+    fn add_primary_index(
+        &self,
+        constraint_name: Option<&str>,
+        columns: &[String],
+        add_clause: bool,
+    ) -> Result<String>;
+
+    fn add_unique_constraint(
+        &self,
+        constraint_name: &str,
+        columns: &[String],
+        nulls_not_distinct: bool,
+        add_clause: bool,
+    ) -> Result<String>;
+
+    fn add_unique_constraint_using_index(
+        &self,
+        constraint_name: &str,
+        index_name: &str,
+    ) -> Result<String>;
+
+    fn add_primary_index_using_index(&self, index_name: &str) -> Result<String>;
+
+    fn drop_unique_constraint(&self, constraint_name: &str) -> Result<String>;
+
+    fn add_check_constraint(
+        &self,
+        constraint_name: &str,
+        condition: &Expr,
+        add_clause: bool,
+        not_valid: bool,
+    ) -> Result<String>;
+
+    fn drop_check_constraint(&self, constraint_name: &str) -> Result<String>;
+
+    fn drop_constraint(
+        &self,
+        constraint_name: &str,
+        if_exists: bool,
+        cascade: bool,
+    ) -> Result<String>;
+
+    fn rename_constraint(&self, constraint_name: &str, new_constraint_name: &str)
+        -> Result<String>;
+
+    fn drop_primary_key(&self, table_name: &str, constraint_name: Option<&str>) -> Result<String>;
+
+    fn validate_constraint(&self, constraint_name: &str) -> Result<String>;
+
+    fn drop_index(
+        &self,
+        table_name: Option<&str>,
+        index_name: &str,
+        if_exists: bool,
+        concurrently: bool,
+    ) -> Result<String>;
+
+    fn rename_index(&self, index_name: &str, new_index_name: &str) -> Result<String>;
+
+    fn attach_index_partition(
+        &self,
+        index_name: &str,
+        partition_index_name: &str,
+    ) -> Result<String>;
+
+    fn reindex_index(&self, index_name: &str, concurrently: bool) -> Result<String>;
+
+    fn reindex_table(&self, table_name: &str) -> Result<String>;
+
+    /// Replaces the primary key constraint `constraint_name` (or the
+    /// dialect's default name of the primary key) with one on `columns`.
+    fn replace_primary_key_concurrently(
+        &self,
+        table_name: &str,
+        columns: &[String],
+        constraint_name: Option<&str>,
+    ) -> Result<String>;
+
+    fn rename_view(&self, name: &str, new_view_name: &str) -> Result<String>;
+
+    fn alter_view_owner(&self, name: &str, role: &str) -> Result<String>;
+
+    fn alter_view_set_options(&self, name: &str, options: &[(String, String)]) -> Result<String>;
+
+    fn create_extension(&self, name: &str) -> Result<String>;
+
+    /// Prerequisites for [DefaultConstraint::RandomUuid][crate::column::DefaultConstraint::RandomUuid],
+    /// empty if none are needed.
+    fn ensure_uuid_generation(&self) -> Result<String>;
+
+    fn insert_rows(
+        &self,
+        table_name: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+    ) -> Result<String>;
+
+    /// Limits the time a following statement waits for a lock.
+    fn lock_timeout(&self, timeout: Duration) -> Result<String>;
+
+    /// Restores the default lock timeout after the statement limited by
+    /// [SqlDialect::lock_timeout]. Empty, if the limit ends by itself.
+    fn reset_lock_timeout(&self) -> Result<String>;
+
+    /// Creates a trigger, which increments the `version` column of the table
+    /// on every update.
+    fn version_trigger(&self, table_name: &str) -> Result<String>;
 
-    fn add_primary_index(&self, columns: &Vec<String>) -> String;
+    /// Creates the `{table}_history` table and a trigger, which records all
+    /// row changes of the table with the given columns.
+    fn audit_history(&self, table_name: &str, columns: &[(String, ColumnType)]) -> Result<String>;
 
-    fn add_unique_constraint(&self, constraint_name: &str, columns: &Vec<String>) -> String;
+    /// Guards the given DDL, so it is only executed if the precondition holds.
+    fn only_if(&self, precondition: &Precondition, ddl: &str) -> Result<String>;
+
+    fn create_composite_type(&self, name: &str, fields: &[(String, ColumnType)]) -> Result<String>;
+
+    /// Creates an enum type. Dialects without native enums may return an
+    /// empty string and emulate the enum on the column (see
+    /// [ColumnType::Enum]).
+    fn create_enum_type(&self, name: &str, values: &[String]) -> Result<String>;
+
+    fn create_domain(
+        &self,
+        name: &str,
+        ct: &ColumnType,
+        not_null: bool,
+        checks: &[String],
+    ) -> Result<String>;
+
+    fn drop_domain(&self, name: &str) -> Result<String>;
+
+    fn add_enum_value(
+        &self,
+        name: &str,
+        value: &str,
+        position: &Option<EnumValuePosition>,
+    ) -> Result<String>;
 
-    fn column_type(&self, ct: &ColumnType) -> String;
+    fn rename_type(&self, name: &str, new_type_name: &str) -> Result<String>;
+
+    fn drop_type(&self, name: &str) -> Result<String>;
+
+    fn expr(&self, expr: &Expr) -> Result<String>;
+
+    fn column_type(&self, ct: &ColumnType) -> Result<String>;
+
+    fn constraints(&self, constraints: &Constraints) -> Result<String>;
+}
+
+/// Options, which control the formatting of the generated DDL without changing
+/// its meaning (see `with_format_options` of the dialects).
+#[derive(Debug, Clone, Default)]
+pub struct FormatOptions {
+    pub(crate) explicit_null: bool,
+}
+
+impl FormatOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renders `NULL` for nullable columns instead of omitting it.
+    pub fn explicit_null(mut self, explicit_null: bool) -> Self {
+        self.explicit_null = explicit_null;
+
+        self
+    }
+}
+
+/// Renders an optional fractional-second precision of temporal types.
+#[cfg(any(feature = "postgres", feature = "mssql"))]
+pub(crate) fn precision(p: &Option<u8>) -> String {
+    p.map(|p| format!("({})", p)).unwrap_or_default()
+}
+
+/// Policy, how a dialect renders a `VARCHAR` column without a length.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnboundedVarchar {
+    /// Uses the dialect's unbounded variant (`varchar` on postgres,
+    /// `NVARCHAR(MAX)` on T-SQL).
+    Native,
+    /// Falls back to the given length.
+    Length(usize),
+    /// Refuses to render the column, so every length has to be explicit.
+    Reject,
+}
+
+/// Hook to override how a [ColumnType] is rendered by a dialect, e.g. to
+/// enforce house rules like rendering every `VARCHAR` as `CITEXT`.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "postgres")] {
+/// use sql_press::{
+///     column::ColumnType,
+///     sql_dialect::{Postgres, SqlDialect, TypeMapping},
+/// };
+///
+/// #[derive(Debug)]
+/// struct HouseRules;
+///
+/// impl TypeMapping for HouseRules {
+///     fn map_type(&self, ct: &ColumnType) -> Option<String> {
+///         match ct {
+///             ColumnType::VARCHAR(_) => Some("citext".into()),
+///             ColumnType::TIMESTAMP(None) => Some("timestamp(3)".into()),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let d = Postgres::new().with_type_mapping(HouseRules);
+/// assert_eq!(d.column_type(&ColumnType::VARCHAR(Some(255))).unwrap(), "citext");
+/// assert_eq!(d.column_type(&ColumnType::TEXT).unwrap(), "text");
+///
+/// // simple overrides can be defined with a closure as well
+/// let d = Postgres::new().with_type_override(|ct| match ct {
+///     ColumnType::TEXT => Some("citext".into()),
+///     _ => None,
+/// });
+/// assert_eq!(d.column_type(&ColumnType::TEXT).unwrap(), "citext");
+/// # }
+/// ```
+pub trait TypeMapping: Debug {
+    /// Returns the rendered type or `None` to fall back to the dialect's
+    /// built-in mapping.
+    fn map_type(&self, ct: &ColumnType) -> Option<String>;
+}
+
+/// [TypeMapping] backed by a closure (see `with_type_override` of the
+/// dialects).
+#[cfg(any(feature = "postgres", feature = "mssql"))]
+pub(crate) struct FnTypeMapping<F>(pub(crate) F);
+
+#[cfg(any(feature = "postgres", feature = "mssql"))]
+impl<F> Debug for FnTypeMapping<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FnTypeMapping")
+    }
+}
 
-    fn constraints(&self, constraints: &Constraints) -> String;
+#[cfg(any(feature = "postgres", feature = "mssql"))]
+impl<F: Fn(&ColumnType) -> Option<String>> TypeMapping for FnTypeMapping<F> {
+    fn map_type(&self, ct: &ColumnType) -> Option<String> {
+        (self.0)(ct)
+    }
 }
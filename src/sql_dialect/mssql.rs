@@ -0,0 +1,1680 @@
+//! Microsoft SQL Server implementation of the [SqlDialect] trait. Translates
+//! all structured changes to T-SQL specific DDL.
+use std::{rc::Rc, time::Duration};
+
+use crate::{
+    column::{ColumnType, Constraints, DefaultConstraint, Storage},
+    error::{Result, SqlPressError},
+    expr::{Expr, ExprStyle, Value},
+    ident::Ident,
+    index::{
+        ForeignKeyMatch, ForeignKeyOptions, IndexColumn, IndexMethod, IndexOptions,
+        ReferentialAction, SortOrder,
+    },
+    precondition::Precondition,
+    table::PartitionBound,
+    types::EnumValuePosition,
+};
+
+use super::{precision, FnTypeMapping, FormatOptions, SqlDialect, TypeMapping, UnboundedVarchar};
+
+#[derive(Debug, Clone)]
+pub struct Mssql {
+    pub(crate) schema: String,
+    pub(crate) type_mapping: Option<Rc<dyn TypeMapping>>,
+    pub(crate) format: FormatOptions,
+    pub(crate) unbounded_varchar: UnboundedVarchar,
+}
+
+impl Mssql {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Convenience method to directly return a [std::rc::Rc] of this struct.
+    pub fn new_rc() -> Rc<Self> {
+        Rc::new(Self::new())
+    }
+
+    /// Qualifies all objects with the given schema instead of the default one.
+    pub fn with_schema(mut self, schema: &str) -> Self {
+        self.schema = schema.into();
+
+        self
+    }
+
+    /// Overrides the built-in [ColumnType] mapping of this dialect with the
+    /// given [TypeMapping].
+    pub fn with_type_mapping<M: TypeMapping + 'static>(mut self, mapping: M) -> Self {
+        self.type_mapping = Some(Rc::new(mapping));
+
+        self
+    }
+
+    /// Overrides the built-in [ColumnType] mapping with the given closure,
+    /// which returns `None` to fall back to the built-in mapping (see
+    /// [TypeMapping]).
+    pub fn with_type_override<F>(self, f: F) -> Self
+    where
+        F: Fn(&ColumnType) -> Option<String> + 'static,
+    {
+        self.with_type_mapping(FnTypeMapping(f))
+    }
+
+    /// Sets the policy for `VARCHAR` columns without a length (defaults to
+    /// [UnboundedVarchar::Native]).
+    pub fn with_unbounded_varchar(mut self, policy: UnboundedVarchar) -> Self {
+        self.unbounded_varchar = policy;
+
+        self
+    }
+
+    /// Changes the formatting of the generated DDL with the given
+    /// [FormatOptions].
+    pub fn with_format_options(mut self, options: FormatOptions) -> Self {
+        self.format = options;
+
+        self
+    }
+}
+
+impl Mssql {
+    fn quote(&self, name: &str) -> Result<String> {
+        self.quote_ident(&Ident::new(name))
+    }
+
+    /// Quotes the given name and qualifies it with the schema.
+    fn qualified(&self, name: &str) -> Result<String> {
+        Ok(format!(
+            "{}.{}",
+            self.quote(&self.schema)?,
+            self.quote(name)?
+        ))
+    }
+
+    /// Renders the given text as a unicode string literal.
+    fn literal(&self, text: &str) -> String {
+        format!("N'{}'", text.replace('\'', "''"))
+    }
+
+    /// Renders an unquoted name as string literal (e.g. for `COL_LENGTH`),
+    /// after validating it like a quoted identifier.
+    fn name_literal(&self, name: &str) -> Result<String> {
+        self.quote(name)?;
+
+        Ok(self.literal(name))
+    }
+
+    fn quote_all(&self, names: &[String]) -> Result<String> {
+        Ok(names
+            .iter()
+            .map(|n| self.quote(n))
+            .collect::<Result<Vec<String>>>()?
+            .join(", "))
+    }
+
+    /// Quotes the columns of an index together with their sort order.
+    fn index_columns(&self, columns: &[IndexColumn]) -> Result<String> {
+        Ok(columns
+            .iter()
+            .map(|c| {
+                if c.nulls.is_some() {
+                    return Err(SqlPressError::unsupported(
+                        "T-SQL does not support NULLS FIRST/LAST in indexes",
+                    ));
+                }
+                if c.opclass.is_some() {
+                    return Err(SqlPressError::unsupported(
+                        "T-SQL does not support operator classes",
+                    ));
+                }
+                Ok(format!(
+                    "{}{}",
+                    self.quote_ident(&c.name)?,
+                    match c.order {
+                        None => "",
+                        Some(SortOrder::Asc) => " ASC",
+                        Some(SortOrder::Desc) => " DESC",
+                    }
+                ))
+            })
+            .collect::<Result<Vec<String>>>()?
+            .join(", "))
+    }
+
+    fn referential_actions(&self, options: &ForeignKeyOptions) -> Result<String> {
+        let action = |a: &ReferentialAction| match a {
+            ReferentialAction::NoAction => Ok("NO ACTION"),
+            ReferentialAction::Restrict => Err(SqlPressError::unsupported(
+                "T-SQL does not support RESTRICT, use NO ACTION instead",
+            )),
+            ReferentialAction::Cascade => Ok("CASCADE"),
+            ReferentialAction::SetNull => Ok("SET NULL"),
+            ReferentialAction::SetDefault => Ok("SET DEFAULT"),
+        };
+
+        let mut ddl = String::new();
+        if let Some(a) = &options.on_delete {
+            ddl.push_str(&format!(" ON DELETE {}", action(a)?));
+        }
+        if let Some(a) = &options.on_update {
+            ddl.push_str(&format!(" ON UPDATE {}", action(a)?));
+        }
+
+        Ok(ddl)
+    }
+}
+
+impl Default for Mssql {
+    fn default() -> Self {
+        Self {
+            schema: "dbo".into(),
+            type_mapping: None,
+            format: FormatOptions::default(),
+            unbounded_varchar: UnboundedVarchar::Native,
+        }
+    }
+}
+
+impl SqlDialect for Mssql {
+    fn name(&self) -> &'static str {
+        "mssql"
+    }
+
+    fn for_schema(&self, schema: &str) -> Rc<dyn SqlDialect> {
+        Rc::new(self.clone().with_schema(schema))
+    }
+
+    fn quote_ident(&self, ident: &Ident) -> Result<String> {
+        ident.validate()?;
+        if !ident.is_raw() && ident.as_str().chars().count() > 128 {
+            return Err(SqlPressError::invalid(format!(
+                "T-SQL identifiers must not be longer than 128 characters: {}",
+                ident.as_str()
+            )));
+        }
+
+        Ok(ident.quoted('[', ']'))
+    }
+
+    fn create_table(
+        &self,
+        name: &str,
+        changes: Vec<String>,
+        if_not_exists: bool,
+    ) -> Result<String> {
+        Ok(format!(
+            "{}CREATE TABLE {} (\n{}\n);",
+            if if_not_exists {
+                format!(
+                    "IF OBJECT_ID({}, N'U') IS NULL\n",
+                    self.literal(&self.qualified(name)?)
+                )
+            } else {
+                "".into()
+            },
+            self.qualified(name)?,
+            changes.join(",\n")
+        ))
+    }
+
+    /// T-SQL does not allow mixing different actions (e.g. `ADD` and `DROP
+    /// COLUMN`) within one `ALTER TABLE` statement, hence every change is
+    /// rendered as its own statement.
+    fn alter_table(&self, name: &str, changes: Vec<String>, if_exists: bool) -> Result<String> {
+        let table = self.qualified(name)?;
+        let statements = changes
+            .iter()
+            .map(|c| format!("ALTER TABLE {} {};", table, c))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        if if_exists {
+            Ok(format!(
+                "IF OBJECT_ID({}, N'U') IS NOT NULL\nBEGIN\n{}\nEND;",
+                self.literal(&table),
+                statements
+            ))
+        } else {
+            Ok(statements)
+        }
+    }
+
+    fn rename_table(&self, name: &str, new_table_name: &str) -> Result<String> {
+        Ok(format!(
+            "EXEC sp_rename {}, {};",
+            self.literal(&self.qualified(name)?),
+            self.name_literal(new_table_name)?
+        ))
+    }
+
+    fn drop_table(&self, name: &str, if_exists: bool, cascade: bool) -> Result<String> {
+        if cascade {
+            return Err(SqlPressError::unsupported(
+                "T-SQL does not support CASCADE when dropping tables",
+            ));
+        }
+        Ok(format!(
+            "DROP TABLE {}{};",
+            if if_exists { "IF EXISTS " } else { "" },
+            self.qualified(name)?
+        ))
+    }
+
+    fn attach_partition(
+        &self,
+        _table_name: &str,
+        _partition_name: &str,
+        _bound: &PartitionBound,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL partitions tables via partition functions, please use a script instead",
+        ))
+    }
+
+    fn detach_partition(
+        &self,
+        _table_name: &str,
+        _partition_name: &str,
+        _concurrently: bool,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL partitions tables via partition functions, please use a script instead",
+        ))
+    }
+
+    fn drop_tables(&self, names: &[String], cascade: bool) -> Result<String> {
+        if cascade {
+            return Err(SqlPressError::unsupported(
+                "T-SQL does not support CASCADE when dropping tables",
+            ));
+        }
+        Ok(format!(
+            "DROP TABLE {};",
+            names
+                .iter()
+                .map(|n| self.qualified(n))
+                .collect::<Result<Vec<String>>>()?
+                .join(", ")
+        ))
+    }
+
+    fn add_column(
+        &self,
+        name: &str,
+        with_prefix: bool,
+        ct: &ColumnType,
+        constraints: &Constraints,
+    ) -> Result<String> {
+        // enums are emulated with a check constraint on the column
+        let check = match ct {
+            ColumnType::Enum { values, .. } => format!(
+                " CHECK ({})",
+                match values
+                    .iter()
+                    .map(|v| Expr::col(name).eq(Expr::val(v.as_str())))
+                    .reduce(|a, b| a.or(b))
+                {
+                    Some(e) => self.expr(&e)?,
+                    None => "".into(),
+                }
+            ),
+            _ => "".into(),
+        };
+
+        // computed columns derive their type from the expression
+        let definition = match &constraints.generated {
+            Some(e) => format!("AS ({}) PERSISTED", self.expr(e)?),
+            None => self.column_type(ct)?,
+        };
+
+        Ok(format!(
+            "{}{} {}{}{}",
+            if with_prefix { "ADD " } else { "" },
+            self.quote(name)?,
+            definition,
+            self.constraints(constraints)?,
+            check
+        ))
+    }
+
+    fn rename_column(
+        &self,
+        table_name: &str,
+        name: &str,
+        new_name: &str,
+        if_exists: bool,
+    ) -> Result<String> {
+        let table = self.qualified(table_name)?;
+        let statement = format!(
+            "EXEC sp_rename {}, {}, 'COLUMN';",
+            self.literal(&format!("{}.{}", table, self.quote(name)?)),
+            self.name_literal(new_name)?
+        );
+
+        if if_exists {
+            Ok(format!(
+                "IF OBJECT_ID({}, N'U') IS NOT NULL\nBEGIN\n{}\nEND;",
+                self.literal(&table),
+                statement
+            ))
+        } else {
+            Ok(statement)
+        }
+    }
+
+    fn alter_column(
+        &self,
+        name: &str,
+        ct: &ColumnType,
+        conversion_method: Option<&str>,
+    ) -> Result<String> {
+        if conversion_method.is_some() {
+            return Err(SqlPressError::unsupported(
+                "T-SQL does not support a conversion method when altering a column type",
+            ));
+        }
+        Ok(format!(
+            "ALTER COLUMN {} {}",
+            self.quote(name)?,
+            self.column_type(ct)?
+        ))
+    }
+
+    fn drop_column(&self, name: &str, if_exists: bool) -> Result<String> {
+        Ok(format!(
+            "DROP COLUMN {}{}",
+            if if_exists { "IF EXISTS " } else { "" },
+            self.quote(name)?
+        ))
+    }
+
+    fn set_column_storage(&self, _name: &str, _storage: &Storage) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL does not support column storage strategies",
+        ))
+    }
+
+    fn set_column_compression(&self, _name: &str, _compression: &str) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL only supports compression per table or index",
+        ))
+    }
+
+    /// Tablespaces are mapped to filegroups.
+    fn add_index(
+        &self,
+        table_name: &str,
+        columns: &[IndexColumn],
+        idx_name: &Option<String>,
+        options: &IndexOptions,
+    ) -> Result<String> {
+        if options.only {
+            return Err(SqlPressError::unsupported(
+                "T-SQL does not support partitioned indexes",
+            ));
+        }
+        if options.if_not_exists {
+            return Err(SqlPressError::unsupported(
+                "T-SQL does not support IF NOT EXISTS for indexes, please use a script instead",
+            ));
+        }
+        if options.concurrently {
+            return Err(SqlPressError::unsupported(
+                "T-SQL does not support CONCURRENTLY, please use the `online` storage parameter instead",
+            ));
+        }
+        if let Some(m) = options.method.filter(|m| *m != IndexMethod::BTree) {
+            return Err(SqlPressError::unsupported(format!(
+                "T-SQL does not support {:?} indexes",
+                m
+            )));
+        }
+        let Some(idx_name) = idx_name else {
+            return Err(SqlPressError::invalid(
+                "T-SQL requires a name for every index",
+            ));
+        };
+        Ok(format!(
+            "CREATE {}INDEX {} ON {} ({}){}{}{}{};",
+            if options.unique { "UNIQUE " } else { "" },
+            self.quote(idx_name)?,
+            self.qualified(table_name)?,
+            self.index_columns(columns)?,
+            if options.include.is_empty() {
+                "".into()
+            } else {
+                format!(" INCLUDE ({})", self.quote_all(&options.include)?)
+            },
+            match &options.where_clause {
+                Some(c) => format!(" WHERE {}", self.expr(c)?),
+                None => "".into(),
+            },
+            if options.storage_parameters.is_empty() {
+                "".into()
+            } else {
+                format!(
+                    " WITH ({})",
+                    options
+                        .storage_parameters
+                        .iter()
+                        .map(|(k, v)| format!("{} = {}", k.to_uppercase(), v))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            },
+            match &options.tablespace {
+                Some(t) => format!(" ON {}", self.quote(t)?),
+                None => "".into(),
+            }
+        ))
+    }
+
+    fn add_foreign_index(
+        &self,
+        columns: &[String],
+        foreign_table_name: &str,
+        foreign_columns: &[String],
+        idx_name: Option<String>,
+        add_clause: &bool,
+        options: &ForeignKeyOptions,
+    ) -> Result<String> {
+        if !matches!(options.match_type, None | Some(ForeignKeyMatch::Simple)) {
+            return Err(SqlPressError::unsupported(
+                "T-SQL only supports MATCH SIMPLE semantics for foreign keys",
+            ));
+        }
+        if options.not_valid {
+            return Err(SqlPressError::unsupported("T-SQL only supports unvalidated foreign keys via WITH NOCHECK, please use a script instead"));
+        }
+        Ok(format!(
+            "{}{}FOREIGN KEY({}) REFERENCES {}({}){}",
+            if *add_clause { "ADD " } else { "" },
+            match idx_name {
+                Some(x) => format!("CONSTRAINT {} ", self.quote(&x)?),
+                None => "".into(),
+            },
+            self.quote_all(columns)?,
+            self.qualified(foreign_table_name)?,
+            self.quote_all(foreign_columns)?,
+            self.referential_actions(options)?
+        ))
+    }
+
+    fn add_primary_index(
+        &self,
+        constraint_name: Option<&str>,
+        columns: &[String],
+        add_clause: bool,
+    ) -> Result<String> {
+        Ok(format!(
+            "{}{}PRIMARY KEY({})",
+            if add_clause { "ADD " } else { "" },
+            match constraint_name {
+                Some(x) => format!("CONSTRAINT {} ", self.quote(x)?),
+                None => "".into(),
+            },
+            self.quote_all(columns)?
+        ))
+    }
+
+    /// Unique constraints in T-SQL always treat `NULL` values as equal, so
+    /// `nulls_not_distinct` doesn't change the rendered constraint.
+    fn add_unique_constraint(
+        &self,
+        constraint_name: &str,
+        columns: &[String],
+        _nulls_not_distinct: bool,
+        add_clause: bool,
+    ) -> Result<String> {
+        if columns.len() < 2 {
+            return Err(SqlPressError::invalid("This only supports multi-column unique contrainst. For single columns, please just use the .unique() function"));
+        }
+        Ok(format!(
+            "{}CONSTRAINT {} UNIQUE ({})",
+            if add_clause { "ADD " } else { "" },
+            self.quote(constraint_name)?,
+            self.quote_all(columns)?
+        ))
+    }
+
+    fn add_unique_constraint_using_index(
+        &self,
+        _constraint_name: &str,
+        _index_name: &str,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL does not support promoting an index to a constraint",
+        ))
+    }
+
+    fn add_primary_index_using_index(&self, _index_name: &str) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL does not support promoting an index to a constraint",
+        ))
+    }
+
+    fn drop_unique_constraint(&self, constraint_name: &str) -> Result<String> {
+        Ok(format!("DROP CONSTRAINT {}", self.quote(constraint_name)?))
+    }
+
+    fn add_check_constraint(
+        &self,
+        constraint_name: &str,
+        condition: &Expr,
+        add_clause: bool,
+        not_valid: bool,
+    ) -> Result<String> {
+        if not_valid {
+            return Err(SqlPressError::unsupported("T-SQL only supports unvalidated check constraints via WITH NOCHECK, please use a script instead"));
+        }
+        Ok(format!(
+            "{}CONSTRAINT {} CHECK ({})",
+            if add_clause { "ADD " } else { "" },
+            self.quote(constraint_name)?,
+            self.expr(condition)?
+        ))
+    }
+
+    fn drop_check_constraint(&self, constraint_name: &str) -> Result<String> {
+        Ok(format!("DROP CONSTRAINT {}", self.quote(constraint_name)?))
+    }
+
+    fn drop_constraint(
+        &self,
+        constraint_name: &str,
+        if_exists: bool,
+        cascade: bool,
+    ) -> Result<String> {
+        if cascade {
+            return Err(SqlPressError::unsupported(
+                "T-SQL does not support dropping constraints with CASCADE",
+            ));
+        }
+        Ok(format!(
+            "DROP CONSTRAINT {}{}",
+            if if_exists { "IF EXISTS " } else { "" },
+            self.quote(constraint_name)?
+        ))
+    }
+
+    fn validate_constraint(&self, constraint_name: &str) -> Result<String> {
+        Ok(format!(
+            "WITH CHECK CHECK CONSTRAINT {}",
+            self.quote(constraint_name)?
+        ))
+    }
+
+    fn drop_primary_key(&self, _table_name: &str, constraint_name: Option<&str>) -> Result<String> {
+        match constraint_name {
+            Some(n) => Ok(format!("DROP CONSTRAINT {}", self.quote(n)?)),
+            None => Err(SqlPressError::unsupported("T-SQL generates the name of the primary key constraint, please pass its name to drop_primary_key")),
+        }
+    }
+
+    fn rename_constraint(
+        &self,
+        _constraint_name: &str,
+        _new_constraint_name: &str,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "Renaming constraints requires `sp_rename`, please use a script instead",
+        ))
+    }
+
+    fn drop_index(
+        &self,
+        table_name: Option<&str>,
+        index_name: &str,
+        if_exists: bool,
+        concurrently: bool,
+    ) -> Result<String> {
+        let Some(table_name) = table_name else {
+            return Err(SqlPressError::unsupported("T-SQL requires the table name to drop an index, please use drop_index within alter_table"));
+        };
+        if concurrently {
+            return Err(SqlPressError::unsupported(
+                "T-SQL does not support dropping an index concurrently",
+            ));
+        }
+        Ok(format!(
+            "DROP INDEX {}{} ON {};",
+            if if_exists { "IF EXISTS " } else { "" },
+            self.quote(index_name)?,
+            self.qualified(table_name)?
+        ))
+    }
+
+    fn rename_index(&self, _index_name: &str, _new_index_name: &str) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "Renaming indexes requires `sp_rename`, please use a script instead",
+        ))
+    }
+
+    fn attach_index_partition(
+        &self,
+        _index_name: &str,
+        _partition_index_name: &str,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL does not support attaching partition indexes",
+        ))
+    }
+
+    fn reindex_index(&self, _index_name: &str, _concurrently: bool) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL requires the table name to rebuild a single index",
+        ))
+    }
+
+    fn reindex_table(&self, table_name: &str) -> Result<String> {
+        Ok(format!(
+            "ALTER INDEX ALL ON {} REBUILD;",
+            self.qualified(table_name)?
+        ))
+    }
+
+    fn replace_primary_key_concurrently(
+        &self,
+        _table_name: &str,
+        _columns: &[String],
+        _constraint_name: Option<&str>,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL does not support promoting an index to a primary key",
+        ))
+    }
+
+    fn rename_view(&self, name: &str, new_view_name: &str) -> Result<String> {
+        Ok(format!(
+            "EXEC sp_rename {}, {};",
+            self.literal(&self.qualified(name)?),
+            self.name_literal(new_view_name)?
+        ))
+    }
+
+    fn alter_view_owner(&self, name: &str, role: &str) -> Result<String> {
+        Ok(format!(
+            "ALTER AUTHORIZATION ON OBJECT::{} TO {};",
+            self.qualified(name)?,
+            self.quote(role)?
+        ))
+    }
+
+    fn alter_view_set_options(&self, _name: &str, _options: &[(String, String)]) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL view options require ALTER VIEW with the full view definition",
+        ))
+    }
+
+    fn create_extension(&self, _name: &str) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL does not support extensions",
+        ))
+    }
+
+    /// `NEWID()` is built-in.
+    fn ensure_uuid_generation(&self) -> Result<String> {
+        Ok("".into())
+    }
+
+    fn insert_rows(
+        &self,
+        table_name: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+    ) -> Result<String> {
+        Ok(format!(
+            "INSERT INTO {} ({}) VALUES\n{};",
+            self.qualified(table_name)?,
+            self.quote_all(columns)?,
+            rows.iter()
+                .map(|r| {
+                    Ok(format!(
+                        "({})",
+                        r.iter()
+                            .map(|v| self.expr(&Expr::Value(v.clone())))
+                            .collect::<Result<Vec<String>>>()?
+                            .join(", ")
+                    ))
+                })
+                .collect::<Result<Vec<String>>>()?
+                .join(",\n")
+        ))
+    }
+
+    fn lock_timeout(&self, timeout: Duration) -> Result<String> {
+        Ok(format!("SET LOCK_TIMEOUT {};", timeout.as_millis()))
+    }
+
+    /// `SET LOCK_TIMEOUT` applies to the whole session, `-1` waits forever.
+    fn reset_lock_timeout(&self) -> Result<String> {
+        Ok("SET LOCK_TIMEOUT -1;".into())
+    }
+
+    fn version_trigger(&self, _table_name: &str) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL triggers can't modify the updated row, please use a ROWVERSION column instead",
+        ))
+    }
+
+    /// `CREATE TRIGGER` has to be the only statement of a batch, hence the
+    /// trigger is separated from the history table by `GO`, which requires a
+    /// client that understands batch separators (e.g. `sqlcmd`).
+    fn audit_history(&self, table_name: &str, columns: &[(String, ColumnType)]) -> Result<String> {
+        let history = format!("{}_history", table_name);
+
+        let mut changed_at = Constraints::new();
+        changed_at.not_null = true;
+        changed_at.default = DefaultConstraint::Expr(Expr::func("SYSDATETIMEOFFSET", Vec::new()));
+        let mut changed_by = Constraints::new();
+        changed_by.not_null = true;
+        changed_by.default = DefaultConstraint::Expr(Expr::func("SUSER_SNAME", Vec::new()));
+        let mut operation = Constraints::new();
+        operation.not_null = true;
+
+        let table = self.create_table(
+            &history,
+            columns
+                .iter()
+                .map(|(n, ct)| {
+                    // the history records values, it must not generate them
+                    let ct = match ct {
+                        ColumnType::SERIAL => &ColumnType::INTEGER,
+                        ColumnType::BIGSERIAL => &ColumnType::BIGINT,
+                        ct => ct,
+                    };
+                    self.add_column(n, false, ct, &Constraints::new())
+                })
+                .chain([
+                    self.add_column(
+                        "operation",
+                        false,
+                        &ColumnType::VARCHAR(Some(6)),
+                        &operation,
+                    ),
+                    self.add_column(
+                        "changed_at",
+                        false,
+                        &ColumnType::TIMESTAMPTZ(None),
+                        &changed_at,
+                    ),
+                    self.add_column(
+                        "changed_by",
+                        false,
+                        &ColumnType::VARCHAR(Some(128)),
+                        &changed_by,
+                    ),
+                ])
+                .collect::<Result<Vec<String>>>()?,
+            false,
+        )?;
+
+        let names = columns
+            .iter()
+            .map(|(n, _)| n.clone())
+            .collect::<Vec<String>>();
+        let insert = format!(
+            "INSERT INTO {} ({}, [operation])",
+            self.qualified(&history)?,
+            self.quote_all(&names)?
+        );
+
+        Ok(format!(
+            "{}\nGO\n\nCREATE TRIGGER {} ON {}\nAFTER INSERT, UPDATE, DELETE\nAS\nBEGIN\nSET NOCOUNT ON;\n{}\nSELECT {}, CASE WHEN EXISTS (SELECT 1 FROM deleted) THEN 'UPDATE' ELSE 'INSERT' END FROM inserted;\n{}\nSELECT {}, 'DELETE' FROM deleted WHERE NOT EXISTS (SELECT 1 FROM inserted);\nEND;\nGO",
+            table,
+            self.qualified(&format!("{}_history_trigger", table_name))?,
+            self.qualified(table_name)?,
+            insert,
+            self.quote_all(&names)?,
+            insert,
+            self.quote_all(&names)?
+        ))
+    }
+
+    fn only_if(&self, precondition: &Precondition, ddl: &str) -> Result<String> {
+        let table = |t: &str| -> Result<String> { Ok(self.literal(&self.qualified(t)?)) };
+        let condition = match precondition {
+            Precondition::TableExists(t) => {
+                format!("OBJECT_ID({}, N'U') IS NOT NULL", table(t)?)
+            }
+            Precondition::TableMissing(t) => format!("OBJECT_ID({}, N'U') IS NULL", table(t)?),
+            Precondition::ColumnExists(t, c) => format!(
+                "COL_LENGTH({}, {}) IS NOT NULL",
+                table(t)?,
+                self.name_literal(c)?
+            ),
+            Precondition::ColumnMissing(t, c) => format!(
+                "COL_LENGTH({}, {}) IS NULL",
+                table(t)?,
+                self.name_literal(c)?
+            ),
+        };
+
+        Ok(format!("IF {}\nBEGIN\n{}\nEND;", condition, ddl.trim_end()))
+    }
+
+    fn create_composite_type(
+        &self,
+        _name: &str,
+        _fields: &[(String, ColumnType)],
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported(
+            "T-SQL does not support composite types",
+        ))
+    }
+
+    /// T-SQL has no enum types, enums are emulated with a `CHECK` constraint
+    /// on the column instead.
+    fn create_enum_type(&self, _name: &str, _values: &[String]) -> Result<String> {
+        Ok("".into())
+    }
+
+    /// Domains are rendered as alias types, which don't support `CHECK`
+    /// constraints.
+    fn create_domain(
+        &self,
+        name: &str,
+        ct: &ColumnType,
+        not_null: bool,
+        checks: &[String],
+    ) -> Result<String> {
+        if !checks.is_empty() {
+            return Err(SqlPressError::unsupported(
+                "T-SQL alias types do not support CHECK constraints",
+            ));
+        }
+
+        Ok(format!(
+            "CREATE TYPE {} FROM {}{};",
+            self.qualified(name)?,
+            self.column_type(ct)?,
+            if not_null { " NOT NULL" } else { "" }
+        ))
+    }
+
+    fn drop_domain(&self, name: &str) -> Result<String> {
+        self.drop_type(name)
+    }
+
+    fn add_enum_value(
+        &self,
+        _name: &str,
+        _value: &str,
+        _position: &Option<EnumValuePosition>,
+    ) -> Result<String> {
+        Err(SqlPressError::unsupported("T-SQL emulates enums with CHECK constraints, alter the constraint of the column instead"))
+    }
+
+    fn rename_type(&self, name: &str, new_type_name: &str) -> Result<String> {
+        Ok(format!(
+            "EXEC sp_rename {}, {}, 'USERDATATYPE';",
+            self.literal(&self.qualified(name)?),
+            self.name_literal(new_type_name)?
+        ))
+    }
+
+    fn drop_type(&self, name: &str) -> Result<String> {
+        Ok(format!("DROP TYPE {};", self.qualified(name)?))
+    }
+
+    fn expr(&self, expr: &Expr) -> Result<String> {
+        expr.render(&ExprStyle {
+            quote: ('[', ']'),
+            true_literal: "1",
+            false_literal: "0",
+        })
+    }
+
+    fn column_type(&self, ct: &ColumnType) -> Result<String> {
+        if let Some(t) = self.type_mapping.as_ref().and_then(|m| m.map_type(ct)) {
+            return Ok(t);
+        }
+
+        let unsupported = |msg: &str| Err(SqlPressError::unsupported(msg));
+
+        Ok(match ct {
+            ColumnType::UUID => "UNIQUEIDENTIFIER".into(),
+            ColumnType::BOOL => "BIT".into(),
+            ColumnType::VARCHAR(Some(s)) => format!("NVARCHAR({})", s),
+            ColumnType::VARCHAR(None) => match self.unbounded_varchar {
+                UnboundedVarchar::Native => "NVARCHAR(MAX)".into(),
+                UnboundedVarchar::Length(s) => format!("NVARCHAR({})", s),
+                UnboundedVarchar::Reject => {
+                    return Err(SqlPressError::invalid(
+                        "VARCHAR columns require an explicit length",
+                    ))
+                }
+            },
+            ColumnType::REAL => "REAL".into(),
+            ColumnType::TEXT => "NVARCHAR(MAX)".into(),
+            ColumnType::TIMESTAMP(p) => format!("DATETIME2{}", precision(p)),
+            ColumnType::TIMESTAMPTZ(p) => format!("DATETIMEOFFSET{}", precision(p)),
+            ColumnType::TIME(p) => format!("TIME{}", precision(p)),
+            ColumnType::TIMETZ(_) => {
+                return unsupported("T-SQL does not support time with time zone")
+            }
+            ColumnType::SMALLINT => "SMALLINT".into(),
+            ColumnType::INTEGER => "INT".into(),
+            ColumnType::BIGINT => "BIGINT".into(),
+            ColumnType::SERIAL => "INT IDENTITY(1,1)".into(),
+            ColumnType::BIGSERIAL => "BIGINT IDENTITY(1,1)".into(),
+            // T-SQL has no dedicated JSON type, JSON is stored as text
+            ColumnType::JSONB => "NVARCHAR(MAX)".into(),
+            ColumnType::JSON => "NVARCHAR(MAX)".into(),
+            ColumnType::TSVECTOR | ColumnType::TSQUERY => {
+                return unsupported(
+                    "T-SQL does not support text search types, use a full-text index instead",
+                )
+            }
+            // the default collation of T-SQL is case-insensitive
+            ColumnType::CITEXT => "NVARCHAR(MAX)".into(),
+            ColumnType::HSTORE => return unsupported("T-SQL does not support hstore"),
+            ColumnType::LTREE => return unsupported("T-SQL does not support ltree"),
+            ColumnType::XML => "XML".into(),
+            ColumnType::BINARY => "VARBINARY(MAX)".into(),
+            ColumnType::Enum { values, .. } => format!(
+                "NVARCHAR({})",
+                values.iter().map(|v| v.chars().count()).max().unwrap_or(1)
+            ),
+            ColumnType::Composite(_) => {
+                return unsupported("T-SQL does not support composite types")
+            }
+            ColumnType::Domain(name) => self.qualified(name)?,
+            ColumnType::Custom(t) => t.clone(),
+        })
+    }
+
+    fn constraints(&self, constraints: &Constraints) -> Result<String> {
+        let def_constraint = match &constraints.default {
+            crate::column::DefaultConstraint::None => "".into(),
+            crate::column::DefaultConstraint::Plain(s) => format!("DEFAULT {}", s),
+            crate::column::DefaultConstraint::Expr(e) => format!("DEFAULT {}", self.expr(e)?),
+            crate::column::DefaultConstraint::RandomUuid => "DEFAULT NEWID()".into(),
+            crate::column::DefaultConstraint::CurrentTimestamp => {
+                "DEFAULT CURRENT_TIMESTAMP".into()
+            }
+        };
+        let unique = match (constraints.unique, &constraints.unique_name) {
+            (false, _) => "".into(),
+            (true, None) => "UNIQUE".into(),
+            (true, Some(n)) => format!("CONSTRAINT {} UNIQUE", self.quote(n)?),
+        };
+        let check = match &constraints.check {
+            Some(e) => format!("CHECK ({})", self.expr(e)?),
+            None => "".into(),
+        };
+
+        let c = [
+            if constraints.identity {
+                "IDENTITY(1,1)"
+            } else {
+                ""
+            },
+            if constraints.primary {
+                "PRIMARY KEY"
+            } else {
+                ""
+            },
+            if constraints.not_null {
+                "NOT NULL"
+            } else if self.format.explicit_null
+                && !constraints.primary
+                && !constraints.identity
+                && constraints.generated.is_none()
+            {
+                "NULL"
+            } else {
+                ""
+            },
+            unique.as_ref(),
+            def_constraint.as_ref(),
+            check.as_ref(),
+        ]
+        .iter()
+        .filter(|c| !c.is_empty())
+        .copied()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+        if !c.is_empty() {
+            // prefix with a space
+            Ok(format!(" {}", c))
+        } else {
+            Ok("".into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::column::DefaultConstraint;
+
+    use super::*;
+
+    #[test]
+    fn create_table() {
+        let d = Box::new(Mssql::new());
+        let ddl = d
+            .create_table("tag", vec!["CHANGE 1".into(), "CHANGE 2".into()], false)
+            .unwrap();
+        assert_eq!(ddl, "CREATE TABLE [dbo].[tag] (\nCHANGE 1,\nCHANGE 2\n);");
+
+        let ddl = d.create_table("tag", Vec::new(), true).unwrap();
+        assert_eq!(
+            ddl,
+            "IF OBJECT_ID(N'[dbo].[tag]', N'U') IS NULL\nCREATE TABLE [dbo].[tag] (\n\n);"
+        );
+    }
+
+    #[test]
+    fn alter_table() {
+        let d = Box::new(Mssql::new());
+        let ddl = d
+            .alter_table("tags", vec!["CHANGE 1".into(), "CHANGE 2".into()], false)
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "ALTER TABLE [dbo].[tags] CHANGE 1;\nALTER TABLE [dbo].[tags] CHANGE 2;"
+        );
+
+        let ddl = d
+            .alter_table("tags", vec!["CHANGE 1".into()], true)
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "IF OBJECT_ID(N'[dbo].[tags]', N'U') IS NOT NULL\nBEGIN\nALTER TABLE [dbo].[tags] CHANGE 1;\nEND;"
+        );
+    }
+
+    #[test]
+    fn rename_table() {
+        let d = Box::new(Mssql::new());
+        let ddl = d.rename_table("tags", "tag").unwrap();
+        assert_eq!(ddl, "EXEC sp_rename N'[dbo].[tags]', N'tag';");
+
+        let ddl = d.rename_table("o'brien", "x]'y").unwrap();
+        assert_eq!(ddl, "EXEC sp_rename N'[dbo].[o''brien]', N'x]''y';");
+        assert!(d.rename_table("tags", "").is_err());
+    }
+
+    #[test]
+    fn rename_column() {
+        let d = Box::new(Mssql::new());
+        let ddl = d.rename_column("tags", "id", "id2", false).unwrap();
+        assert_eq!(
+            ddl,
+            "EXEC sp_rename N'[dbo].[tags].[id]', N'id2', 'COLUMN';"
+        );
+
+        let ddl = d.rename_column("tags", "id", "id2", true).unwrap();
+        assert_eq!(
+            ddl,
+            "IF OBJECT_ID(N'[dbo].[tags]', N'U') IS NOT NULL\nBEGIN\nEXEC sp_rename N'[dbo].[tags].[id]', N'id2', 'COLUMN';\nEND;"
+        );
+
+        let ddl = d.rename_column("it's", "a]b", "c'd", true).unwrap();
+        assert_eq!(
+            ddl,
+            "IF OBJECT_ID(N'[dbo].[it''s]', N'U') IS NOT NULL\nBEGIN\nEXEC sp_rename N'[dbo].[it''s].[a]]b]', N'c''d', 'COLUMN';\nEND;"
+        );
+    }
+
+    #[test]
+    fn drop_table() {
+        let d = Box::new(Mssql::new());
+        let ddl = d.drop_table("tags", false, false).unwrap();
+        assert_eq!(ddl, "DROP TABLE [dbo].[tags];");
+
+        let ddl = d.drop_table("tags", true, false).unwrap();
+        assert_eq!(ddl, "DROP TABLE IF EXISTS [dbo].[tags];");
+    }
+
+    #[test]
+    fn drop_tables() {
+        let d = Box::new(Mssql::new());
+        let ddl = d.drop_tables(&["a".into(), "b".into()], false).unwrap();
+        assert_eq!(ddl, "DROP TABLE [dbo].[a], [dbo].[b];");
+    }
+
+    #[test]
+    fn expr() {
+        let d = Mssql::new();
+        let e = Expr::col("active").eq(Expr::val(true));
+        assert_eq!(d.expr(&e).unwrap(), "[active] = 1");
+    }
+
+    #[test]
+    fn unbounded_varchar() {
+        let d = Mssql::new();
+        assert_eq!(
+            d.column_type(&ColumnType::VARCHAR(None)).unwrap(),
+            "NVARCHAR(MAX)"
+        );
+
+        let d = Mssql::new().with_unbounded_varchar(UnboundedVarchar::Length(255));
+        assert_eq!(
+            d.column_type(&ColumnType::VARCHAR(None)).unwrap(),
+            "NVARCHAR(255)"
+        );
+    }
+
+    #[test]
+    fn explicit_null() {
+        let d = Mssql::new().with_format_options(FormatOptions::new().explicit_null(true));
+        let ddl = d
+            .add_column("name", false, &ColumnType::TEXT, &Constraints::new())
+            .unwrap();
+        assert_eq!(ddl, "[name] NVARCHAR(MAX) NULL");
+
+        let mut constraints = Constraints::new();
+        constraints.identity = true;
+        let ddl = d
+            .add_column("id", false, &ColumnType::INTEGER, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "[id] INT IDENTITY(1,1)");
+    }
+
+    #[test]
+    fn add_column() {
+        let d = Box::new(Mssql::new());
+        let ddl = d
+            .add_column("id", false, &ColumnType::UUID, &Constraints::new())
+            .unwrap();
+        assert_eq!(ddl, "[id] UNIQUEIDENTIFIER");
+
+        let ddl = d
+            .add_column("id", true, &ColumnType::UUID, &Constraints::new())
+            .unwrap();
+        assert_eq!(ddl, "ADD [id] UNIQUEIDENTIFIER");
+
+        let mut constraints = Constraints::new();
+        constraints.not_null = true;
+        constraints.default = DefaultConstraint::Plain("NEWID()".into());
+        let ddl = d
+            .add_column("id", false, &ColumnType::UUID, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "[id] UNIQUEIDENTIFIER NOT NULL DEFAULT NEWID()");
+
+        let mut constraints = Constraints::new();
+        constraints.default = DefaultConstraint::CurrentTimestamp;
+        let ddl = d
+            .add_column(
+                "created_at",
+                false,
+                &ColumnType::TIMESTAMP(None),
+                &constraints,
+            )
+            .unwrap();
+        assert_eq!(ddl, "[created_at] DATETIME2 DEFAULT CURRENT_TIMESTAMP");
+
+        let mut constraints = Constraints::new();
+        constraints.default = DefaultConstraint::value(true);
+        let ddl = d
+            .add_column("active", false, &ColumnType::BOOL, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "[active] BIT DEFAULT 1");
+    }
+
+    #[test]
+    fn generated_column() {
+        let d = Box::new(Mssql::new().with_format_options(FormatOptions {
+            explicit_null: true,
+        }));
+        let mut constraints = Constraints::new();
+        constraints.generated = Some(Expr::func("LOWER", vec![Expr::col("email")]));
+
+        let ddl = d
+            .add_column("email_lower", true, &ColumnType::TEXT, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "ADD [email_lower] AS (LOWER([email])) PERSISTED");
+    }
+
+    #[test]
+    fn identity_column() {
+        let d = Box::new(Mssql::new());
+        let mut constraints = Constraints::new();
+        constraints.identity = true;
+        constraints.primary = true;
+
+        let ddl = d
+            .add_column("id", false, &ColumnType::INTEGER, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "[id] INT IDENTITY(1,1) PRIMARY KEY");
+    }
+
+    #[test]
+    fn alter_column() {
+        let d = Box::new(Mssql::new());
+        let ddl = d.alter_column("name", &ColumnType::TEXT, None).unwrap();
+        assert_eq!(ddl, "ALTER COLUMN [name] NVARCHAR(MAX)");
+    }
+
+    #[test]
+    fn drop_column() {
+        let d = Box::new(Mssql::new());
+        assert_eq!(d.drop_column("id", false).unwrap(), "DROP COLUMN [id]");
+        assert_eq!(
+            d.drop_column("id", true).unwrap(),
+            "DROP COLUMN IF EXISTS [id]"
+        );
+    }
+
+    #[test]
+    fn add_index() {
+        let d = Box::new(Mssql::new());
+        let mut options = IndexOptions::new();
+        options.unique = true;
+        options.storage_parameters = vec![("fillfactor".into(), "70".into())];
+        options.tablespace = Some("fast_ssd".into());
+        let ddl = d
+            .add_index(
+                "users",
+                &["email".into()],
+                &Some("idx_users_email".into()),
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE UNIQUE INDEX [idx_users_email] ON [dbo].[users] ([email]) WITH (FILLFACTOR = 70) ON [fast_ssd];"
+        );
+
+        let mut options = IndexOptions::new();
+        options.include = vec!["name".into()];
+        options.where_clause = Some(Expr::col("deleted_at").is_null());
+        let ddl = d
+            .add_index(
+                "users",
+                &["email".into()],
+                &Some("idx_users_email".into()),
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX [idx_users_email] ON [dbo].[users] ([email]) INCLUDE ([name]) WHERE [deleted_at] IS NULL;"
+        );
+
+        let ddl = d
+            .add_index(
+                "orders",
+                &["user_id".into(), IndexColumn::desc("created_at")],
+                &Some("idx_orders_user_id_created_at".into()),
+                &IndexOptions::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX [idx_orders_user_id_created_at] ON [dbo].[orders] ([user_id], [created_at] DESC);"
+        );
+    }
+
+    #[test]
+    fn add_foreign_index() {
+        let d = Box::new(Mssql::new());
+        let ddl = d
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                None,
+                &false,
+                &ForeignKeyOptions::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "FOREIGN KEY([blubb_id]) REFERENCES [dbo].[blubb]([id])"
+        );
+
+        let ddl = d
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                Some("fk_blubb".into()),
+                &true,
+                &ForeignKeyOptions::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "ADD CONSTRAINT [fk_blubb] FOREIGN KEY([blubb_id]) REFERENCES [dbo].[blubb]([id])"
+        );
+
+        let mut options = ForeignKeyOptions::new();
+        options.on_delete = Some(ReferentialAction::Cascade);
+        options.on_update = Some(ReferentialAction::NoAction);
+        let ddl = d
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                None,
+                &false,
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "FOREIGN KEY([blubb_id]) REFERENCES [dbo].[blubb]([id]) ON DELETE CASCADE ON UPDATE NO ACTION"
+        );
+    }
+
+    #[test]
+    fn add_foreign_index_restrict() {
+        let mut options = ForeignKeyOptions::new();
+        options.on_delete = Some(ReferentialAction::Restrict);
+        let err = Mssql::new()
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                None,
+                &false,
+                &options,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("T-SQL does not support RESTRICT"));
+    }
+
+    #[test]
+    fn add_primary_index() {
+        let d = Box::new(Mssql::new());
+        let ddl = d
+            .add_primary_index(None, &["id".into(), "id2".into()], false)
+            .unwrap();
+        assert_eq!(ddl, "PRIMARY KEY([id], [id2])");
+
+        let ddl = d
+            .add_primary_index(Some("pk_orders"), &["id".into()], false)
+            .unwrap();
+        assert_eq!(ddl, "CONSTRAINT [pk_orders] PRIMARY KEY([id])");
+    }
+
+    #[test]
+    fn add_unique_constraint() {
+        let d = Box::new(Mssql::new());
+        let ddl = d
+            .add_unique_constraint("id_id2_unique", &["id".into(), "id2".into()], true, false)
+            .unwrap();
+        assert_eq!(ddl, "CONSTRAINT [id_id2_unique] UNIQUE ([id], [id2])");
+    }
+
+    #[test]
+    fn drop_index() {
+        let d = Box::new(Mssql::new());
+        assert_eq!(
+            d.drop_unique_constraint("uq_users_email").unwrap(),
+            "DROP CONSTRAINT [uq_users_email]"
+        );
+        assert_eq!(
+            d.drop_constraint("fk_order_user", true, false).unwrap(),
+            "DROP CONSTRAINT IF EXISTS [fk_order_user]"
+        );
+        assert_eq!(
+            d.drop_primary_key("orders", Some("pk_orders")).unwrap(),
+            "DROP CONSTRAINT [pk_orders]"
+        );
+        assert!(d.drop_primary_key("orders", None).is_err());
+        assert_eq!(
+            d.drop_index(Some("users"), "idx_users_email", false, false)
+                .unwrap(),
+            "DROP INDEX [idx_users_email] ON [dbo].[users];"
+        );
+        assert_eq!(
+            d.drop_index(Some("users"), "idx_users_email", true, false)
+                .unwrap(),
+            "DROP INDEX IF EXISTS [idx_users_email] ON [dbo].[users];"
+        );
+    }
+
+    #[test]
+    fn reindex_table() {
+        let d = Box::new(Mssql::new());
+        assert_eq!(
+            d.reindex_table("users").unwrap(),
+            "ALTER INDEX ALL ON [dbo].[users] REBUILD;"
+        );
+    }
+
+    #[test]
+    fn quote_ident() {
+        let d = Mssql::new();
+        assert_eq!(
+            d.drop_table("my]table", false, false).unwrap(),
+            "DROP TABLE [dbo].[my]]table];"
+        );
+        assert_eq!(d.quote_ident(&Ident::raw("#tmp")).unwrap(), "#tmp");
+    }
+
+    #[test]
+    fn audit_history() {
+        let d = Mssql::new();
+        let ddl = d
+            .audit_history(
+                "orders",
+                &[
+                    ("id".into(), ColumnType::UUID),
+                    ("number".into(), ColumnType::BIGSERIAL),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE TABLE [dbo].[orders_history] (
+[id] UNIQUEIDENTIFIER,
+[number] BIGINT,
+[operation] NVARCHAR(6) NOT NULL,
+[changed_at] DATETIMEOFFSET NOT NULL DEFAULT SYSDATETIMEOFFSET(),
+[changed_by] NVARCHAR(128) NOT NULL DEFAULT SUSER_SNAME()
+);
+GO
+
+CREATE TRIGGER [dbo].[orders_history_trigger] ON [dbo].[orders]
+AFTER INSERT, UPDATE, DELETE
+AS
+BEGIN
+SET NOCOUNT ON;
+INSERT INTO [dbo].[orders_history] ([id], [number], [operation])
+SELECT [id], [number], CASE WHEN EXISTS (SELECT 1 FROM deleted) THEN 'UPDATE' ELSE 'INSERT' END FROM inserted;
+INSERT INTO [dbo].[orders_history] ([id], [number], [operation])
+SELECT [id], [number], 'DELETE' FROM deleted WHERE NOT EXISTS (SELECT 1 FROM inserted);
+END;
+GO"
+        );
+    }
+
+    #[test]
+    fn enum_column() {
+        let d = Mssql::new();
+        let ct = ColumnType::Enum {
+            name: "orders_status".into(),
+            values: vec!["new".into(), "paid".into()],
+        };
+        assert_eq!(
+            d.create_enum_type("orders_status", &["new".into()])
+                .unwrap(),
+            ""
+        );
+        assert_eq!(
+            d.add_column("status", false, &ct, &Constraints::new())
+                .unwrap(),
+            "[status] NVARCHAR(4) CHECK ([status] = 'new' OR [status] = 'paid')"
+        );
+    }
+
+    #[test]
+    fn insert_rows() {
+        let d = Mssql::new();
+        assert_eq!(
+            d.insert_rows(
+                "countries",
+                &["code".into(), "active".into()],
+                &[vec!["US".into(), true.into()]]
+            )
+            .unwrap(),
+            "INSERT INTO [dbo].[countries] ([code], [active]) VALUES\n('US', 1);"
+        );
+    }
+
+    #[test]
+    fn alter_view() {
+        let d = Mssql::new();
+        assert_eq!(
+            d.rename_view("active_users", "active_customers").unwrap(),
+            "EXEC sp_rename N'[dbo].[active_users]', N'active_customers';"
+        );
+        assert_eq!(
+            d.rename_view("users'", "customers'").unwrap(),
+            "EXEC sp_rename N'[dbo].[users'']', N'customers''';"
+        );
+        assert_eq!(
+            d.alter_view_owner("active_users", "reporting").unwrap(),
+            "ALTER AUTHORIZATION ON OBJECT::[dbo].[active_users] TO [reporting];"
+        );
+    }
+
+    #[test]
+    fn for_schema() {
+        let d = Mssql::new().for_schema("tenant_a");
+        assert_eq!(
+            d.drop_table("tag", false, false).unwrap(),
+            "DROP TABLE [tenant_a].[tag];"
+        );
+    }
+
+    #[test]
+    fn rename_type() {
+        let d = Box::new(Mssql::new());
+        assert_eq!(
+            d.rename_type("address", "postal_address").unwrap(),
+            "EXEC sp_rename N'[dbo].[address]', N'postal_address', 'USERDATATYPE';"
+        );
+        assert_eq!(
+            d.rename_type("it's", "that's").unwrap(),
+            "EXEC sp_rename N'[dbo].[it''s]', N'that''s', 'USERDATATYPE';"
+        );
+    }
+
+    #[test]
+    fn drop_type() {
+        let d = Box::new(Mssql::new());
+        assert_eq!(
+            d.drop_type("address").unwrap(),
+            "DROP TYPE [dbo].[address];"
+        );
+    }
+
+    #[test]
+    fn create_domain() {
+        let d = Box::new(Mssql::new());
+        assert_eq!(
+            d.create_domain("email", &ColumnType::VARCHAR(Some(320)), true, &[])
+                .unwrap(),
+            "CREATE TYPE [dbo].[email] FROM NVARCHAR(320) NOT NULL;"
+        );
+    }
+
+    #[test]
+    fn create_composite_type() {
+        let d = Box::new(Mssql::new());
+        assert_eq!(
+            d.create_composite_type("address", &[("street".into(), ColumnType::TEXT)]),
+            Err(SqlPressError::UnsupportedByDialect(
+                "T-SQL does not support composite types".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn lock_timeout() {
+        let d = Box::new(Mssql::new());
+        assert_eq!(
+            d.lock_timeout(Duration::from_secs(5)).unwrap(),
+            "SET LOCK_TIMEOUT 5000;"
+        );
+        assert_eq!(d.reset_lock_timeout().unwrap(), "SET LOCK_TIMEOUT -1;");
+    }
+
+    #[test]
+    fn only_if() {
+        let d = Box::new(Mssql::new());
+        let ddl = d
+            .only_if(
+                &Precondition::ColumnMissing("tags".into(), "slug".into()),
+                "ALTER TABLE [dbo].[tags] ADD [slug] NVARCHAR(MAX);",
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "IF COL_LENGTH(N'[dbo].[tags]', N'slug') IS NULL\nBEGIN\nALTER TABLE [dbo].[tags] ADD [slug] NVARCHAR(MAX);\nEND;"
+        );
+    }
+
+    #[test]
+    fn only_if_escapes_literals() {
+        let d = Box::new(Mssql::new().with_schema("o'brien"));
+        let ddl = d
+            .only_if(
+                &Precondition::ColumnExists("t".into(), "it's".into()),
+                "SELECT 1;",
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "IF COL_LENGTH(N'[o''brien].[t]', N'it''s') IS NOT NULL\nBEGIN\nSELECT 1;\nEND;"
+        );
+
+        let ddl = d
+            .only_if(&Precondition::TableMissing("it's".into()), "SELECT 1;")
+            .unwrap();
+        assert!(ddl.starts_with("IF OBJECT_ID(N'[o''brien].[it''s]', N'U') IS NULL"));
+    }
+
+    #[test]
+    fn column_type() {
+        let d = Box::new(Mssql::new());
+        assert_eq!(
+            d.column_type(&ColumnType::UUID).unwrap(),
+            "UNIQUEIDENTIFIER"
+        );
+        assert_eq!(d.column_type(&ColumnType::BOOL).unwrap(), "BIT");
+        assert_eq!(
+            d.column_type(&ColumnType::VARCHAR(Some(255))).unwrap(),
+            "NVARCHAR(255)"
+        );
+        assert_eq!(d.column_type(&ColumnType::REAL).unwrap(), "REAL");
+        assert_eq!(d.column_type(&ColumnType::TEXT).unwrap(), "NVARCHAR(MAX)");
+        assert_eq!(
+            d.column_type(&ColumnType::TIMESTAMP(None)).unwrap(),
+            "DATETIME2"
+        );
+        assert_eq!(
+            d.column_type(&ColumnType::TIMESTAMPTZ(None)).unwrap(),
+            "DATETIMEOFFSET"
+        );
+        assert_eq!(
+            d.column_type(&ColumnType::TIMESTAMPTZ(Some(3))).unwrap(),
+            "DATETIMEOFFSET(3)"
+        );
+        assert_eq!(
+            d.column_type(&ColumnType::TIME(Some(0))).unwrap(),
+            "TIME(0)"
+        );
+        assert_eq!(d.column_type(&ColumnType::INTEGER).unwrap(), "INT");
+        assert_eq!(
+            d.column_type(&ColumnType::BINARY).unwrap(),
+            "VARBINARY(MAX)"
+        );
+        assert_eq!(d.column_type(&ColumnType::XML).unwrap(), "XML");
+        assert_eq!(
+            d.column_type(&ColumnType::BIGSERIAL).unwrap(),
+            "BIGINT IDENTITY(1,1)"
+        );
+        assert_eq!(d.column_type(&ColumnType::JSONB).unwrap(), "NVARCHAR(MAX)");
+    }
+}
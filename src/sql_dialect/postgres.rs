@@ -1,14 +1,53 @@
 //! Postgres implementation of the [SqlDialect] trait. Translates all structured
 //! changes to postgres specific SQL DDL.
-use std::rc::Rc;
+use std::{rc::Rc, time::Duration};
 
-use crate::column::{ColumnType, Constraints};
+use crate::{
+    column::{ColumnType, Constraints, DefaultConstraint, Storage},
+    error::{Result, SqlPressError},
+    expr::{Expr, ExprStyle, Value},
+    ident::Ident,
+    index::{
+        ForeignKeyMatch, ForeignKeyOptions, IndexColumn, IndexMethod, IndexOptions, NullsOrder,
+        ReferentialAction, SortOrder,
+    },
+    precondition::Precondition,
+    table::PartitionBound,
+    types::EnumValuePosition,
+};
 
-use super::SqlDialect;
+use super::{precision, FnTypeMapping, FormatOptions, SqlDialect, TypeMapping, UnboundedVarchar};
+
+/// Function, which generates random UUIDs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UuidGeneration {
+    /// `gen_random_uuid()`, built-in since postgres 13
+    Builtin,
+    /// `gen_random_uuid()` of the `pgcrypto` extension
+    Pgcrypto,
+    /// `uuid_generate_v4()` of the `uuid-ossp` extension
+    UuidOssp,
+}
+
+/// Strategy, how auto-incrementing identity columns are rendered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AutoIncrement {
+    /// `serial` pseudo type backed by an implicitly created sequence
+    Serial,
+    /// SQL standard `GENERATED BY DEFAULT AS IDENTITY`
+    Identity,
+}
 
 #[derive(Debug, Clone)]
 pub struct Postgres {
     pub(crate) schema: String,
+    pub(crate) type_mapping: Option<Rc<dyn TypeMapping>>,
+    pub(crate) format: FormatOptions,
+    pub(crate) unbounded_varchar: UnboundedVarchar,
+    pub(crate) auto_increment: AutoIncrement,
+    /// Major version of the targeted server, `None` is the latest one
+    pub(crate) version: Option<u32>,
+    pub(crate) uuid_generation: Option<UuidGeneration>,
 }
 
 impl Postgres {
@@ -22,45 +61,345 @@ impl Postgres {
     pub fn new_rc() -> Rc<Self> {
         Rc::new(Self::new())
     }
+
+    /// Qualifies all objects with the given schema instead of the default one.
+    pub fn with_schema(mut self, schema: &str) -> Self {
+        self.schema = schema.into();
+
+        self
+    }
+
+    /// Overrides the built-in [ColumnType] mapping of this dialect with the
+    /// given [TypeMapping].
+    pub fn with_type_mapping<M: TypeMapping + 'static>(mut self, mapping: M) -> Self {
+        self.type_mapping = Some(Rc::new(mapping));
+
+        self
+    }
+
+    /// Overrides the built-in [ColumnType] mapping with the given closure,
+    /// which returns `None` to fall back to the built-in mapping (see
+    /// [TypeMapping]).
+    pub fn with_type_override<F>(self, f: F) -> Self
+    where
+        F: Fn(&ColumnType) -> Option<String> + 'static,
+    {
+        self.with_type_mapping(FnTypeMapping(f))
+    }
+
+    /// Sets the policy for `VARCHAR` columns without a length (defaults to
+    /// [UnboundedVarchar::Native]).
+    pub fn with_unbounded_varchar(mut self, policy: UnboundedVarchar) -> Self {
+        self.unbounded_varchar = policy;
+
+        self
+    }
+
+    /// Sets how identity columns are rendered (defaults to
+    /// [AutoIncrement::Identity]).
+    pub fn auto_increment_strategy(mut self, strategy: AutoIncrement) -> Self {
+        self.auto_increment = strategy;
+
+        self
+    }
+
+    /// Targets the given major version of postgres, e.g. to use features
+    /// only if they are available.
+    pub fn with_version(mut self, major: u32) -> Self {
+        self.version = Some(major);
+
+        self
+    }
+
+    /// Overrides the function to generate random UUIDs, which defaults to
+    /// [UuidGeneration::Builtin] or [UuidGeneration::Pgcrypto] before
+    /// postgres 13.
+    pub fn with_uuid_generation(mut self, generation: UuidGeneration) -> Self {
+        self.uuid_generation = Some(generation);
+
+        self
+    }
+
+    /// Changes the formatting of the generated DDL with the given
+    /// [FormatOptions].
+    pub fn with_format_options(mut self, options: FormatOptions) -> Self {
+        self.format = options;
+
+        self
+    }
+}
+
+impl Postgres {
+    fn uuid_generation(&self) -> UuidGeneration {
+        match (self.uuid_generation, self.version) {
+            (Some(g), _) => g,
+            (None, Some(v)) if v < 13 => UuidGeneration::Pgcrypto,
+            (None, _) => UuidGeneration::Builtin,
+        }
+    }
+
+    /// `NULLS NOT DISTINCT` clause of a unique constraint, which is
+    /// supported since postgres 15.
+    fn nulls_not_distinct(&self, nulls_not_distinct: bool) -> Result<&'static str> {
+        match (nulls_not_distinct, self.version) {
+            (false, _) => Ok(""),
+            (true, Some(v)) if v < 15 => Err(SqlPressError::unsupported(
+                "UNIQUE NULLS NOT DISTINCT requires postgres 15",
+            )),
+            (true, _) => Ok("NULLS NOT DISTINCT "),
+        }
+    }
+
+    /// Fails with [SqlPressError::UnsupportedByDialect], if the targeted
+    /// version is older than the given one.
+    fn require_version(&self, major: u32, feature: &str) -> Result<()> {
+        match self.version {
+            Some(v) if v < major => Err(SqlPressError::unsupported(format!(
+                "{} requires postgres {}",
+                feature, major
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn quote(&self, name: &str) -> Result<String> {
+        self.quote_ident(&Ident::new(name))
+    }
+
+    /// Quotes the given name and qualifies it with the schema.
+    fn qualified(&self, name: &str) -> Result<String> {
+        Ok(format!(
+            "{}.{}",
+            self.quote(&self.schema)?,
+            self.quote(name)?
+        ))
+    }
+
+    /// Renders the given text as a string literal.
+    fn literal(&self, text: &str) -> String {
+        format!("'{}'", text.replace('\'', "''"))
+    }
+
+    fn quote_all(&self, names: &[String]) -> Result<String> {
+        Ok(names
+            .iter()
+            .map(|n| self.quote(n))
+            .collect::<Result<Vec<String>>>()?
+            .join(", "))
+    }
+
+    /// Quotes the columns of an index together with their sort order.
+    fn index_columns(&self, columns: &[IndexColumn]) -> Result<String> {
+        Ok(columns
+            .iter()
+            .map(|c| {
+                Ok(format!(
+                    "{}{}{}{}",
+                    self.quote_ident(&c.name)?,
+                    c.opclass
+                        .as_ref()
+                        .map(|o| format!(" {}", o))
+                        .unwrap_or_default(),
+                    match c.order {
+                        None => "",
+                        Some(SortOrder::Asc) => " ASC",
+                        Some(SortOrder::Desc) => " DESC",
+                    },
+                    match c.nulls {
+                        None => "",
+                        Some(NullsOrder::First) => " NULLS FIRST",
+                        Some(NullsOrder::Last) => " NULLS LAST",
+                    }
+                ))
+            })
+            .collect::<Result<Vec<String>>>()?
+            .join(", "))
+    }
+
+    fn referential_actions(&self, options: &ForeignKeyOptions) -> String {
+        let action = |a: &ReferentialAction| match a {
+            ReferentialAction::NoAction => "NO ACTION",
+            ReferentialAction::Restrict => "RESTRICT",
+            ReferentialAction::Cascade => "CASCADE",
+            ReferentialAction::SetNull => "SET NULL",
+            ReferentialAction::SetDefault => "SET DEFAULT",
+        };
+
+        let mut ddl = String::new();
+        if let Some(a) = &options.on_delete {
+            ddl.push_str(&format!(" ON DELETE {}", action(a)));
+        }
+        if let Some(a) = &options.on_update {
+            ddl.push_str(&format!(" ON UPDATE {}", action(a)));
+        }
+
+        ddl
+    }
 }
 
 impl Default for Postgres {
     fn default() -> Self {
         Self {
             schema: "public".into(),
+            type_mapping: None,
+            format: FormatOptions::default(),
+            unbounded_varchar: UnboundedVarchar::Native,
+            auto_increment: AutoIncrement::Identity,
+            version: None,
+            uuid_generation: None,
         }
     }
 }
 
 impl SqlDialect for Postgres {
-    fn create_table(&self, name: &str, changes: Vec<String>, if_not_exists: bool) -> String {
-        format!(
-            "CREATE TABLE {}{}.\"{}\" (\n{}\n);",
-            if_not_exists.then(|| "IF NOT EXISTS ").unwrap_or(""),
-            self.schema,
-            name,
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn for_schema(&self, schema: &str) -> Rc<dyn SqlDialect> {
+        Rc::new(self.clone().with_schema(schema))
+    }
+
+    fn quote_ident(&self, ident: &Ident) -> Result<String> {
+        ident.validate()?;
+        if !ident.is_raw() && ident.as_str().len() > 63 {
+            return Err(SqlPressError::invalid(format!(
+                "Postgres identifiers must not be longer than 63 bytes: {}",
+                ident.as_str()
+            )));
+        }
+
+        Ok(ident.quoted('"', '"'))
+    }
+
+    fn create_table(
+        &self,
+        name: &str,
+        changes: Vec<String>,
+        if_not_exists: bool,
+    ) -> Result<String> {
+        Ok(format!(
+            "CREATE TABLE {}{} (\n{}\n);",
+            if if_not_exists { "IF NOT EXISTS " } else { "" },
+            self.qualified(name)?,
             changes.join(",\n")
-        )
+        ))
     }
 
-    fn alter_table(&self, name: &str, changes: Vec<String>) -> String {
-        format!(
-            "ALTER TABLE {}.\"{}\"\n{};",
-            self.schema,
-            name,
+    fn alter_table(&self, name: &str, changes: Vec<String>, if_exists: bool) -> Result<String> {
+        Ok(format!(
+            "ALTER TABLE {}{}\n{};",
+            if if_exists { "IF EXISTS " } else { "" },
+            self.qualified(name)?,
             changes.join(",\n")
-        )
+        ))
     }
 
-    fn rename_table(&self, name: &str, new_table_name: &str) -> String {
-        format!(
-            "ALTER TABLE {}.\"{}\" RENAME TO {}.\"{}\";",
-            self.schema, name, self.schema, new_table_name,
-        )
+    fn rename_table(&self, name: &str, new_table_name: &str) -> Result<String> {
+        Ok(format!(
+            "ALTER TABLE {} RENAME TO {};",
+            self.qualified(name)?,
+            self.quote(new_table_name)?,
+        ))
+    }
+
+    fn drop_table(&self, name: &str, if_exists: bool, cascade: bool) -> Result<String> {
+        Ok(format!(
+            "DROP TABLE {}{}{};",
+            if if_exists { "IF EXISTS " } else { "" },
+            self.qualified(name)?,
+            if cascade { " CASCADE" } else { "" }
+        ))
+    }
+
+    fn drop_tables(&self, names: &[String], cascade: bool) -> Result<String> {
+        Ok(format!(
+            "DROP TABLE {}{};",
+            names
+                .iter()
+                .map(|n| self.qualified(n))
+                .collect::<Result<Vec<String>>>()?
+                .join(", "),
+            if cascade { " CASCADE" } else { "" }
+        ))
+    }
+
+    fn attach_partition(
+        &self,
+        table_name: &str,
+        partition_name: &str,
+        bound: &PartitionBound,
+    ) -> Result<String> {
+        let values = |values: &[Expr]| -> Result<String> {
+            Ok(values
+                .iter()
+                .map(|v| self.expr(v))
+                .collect::<Result<Vec<String>>>()?
+                .join(", "))
+        };
+
+        Ok(format!(
+            "ALTER TABLE {} ATTACH PARTITION {} {};",
+            self.qualified(table_name)?,
+            self.qualified(partition_name)?,
+            match bound {
+                PartitionBound::Range { from, to } => {
+                    format!("FOR VALUES FROM ({}) TO ({})", values(from)?, values(to)?)
+                }
+                PartitionBound::List(v) => format!("FOR VALUES IN ({})", values(v)?),
+                PartitionBound::Hash { modulus, remainder } => format!(
+                    "FOR VALUES WITH (MODULUS {}, REMAINDER {})",
+                    modulus, remainder
+                ),
+                PartitionBound::Default => "DEFAULT".into(),
+            }
+        ))
+    }
+
+    fn detach_partition(
+        &self,
+        table_name: &str,
+        partition_name: &str,
+        concurrently: bool,
+    ) -> Result<String> {
+        if concurrently {
+            self.require_version(14, "DETACH PARTITION CONCURRENTLY")?;
+        }
+        Ok(format!(
+            "ALTER TABLE {} DETACH PARTITION {}{};",
+            self.qualified(table_name)?,
+            self.qualified(partition_name)?,
+            if concurrently { " CONCURRENTLY" } else { "" }
+        ))
+    }
+
+    fn validate_constraint(&self, constraint_name: &str) -> Result<String> {
+        Ok(format!(
+            "VALIDATE CONSTRAINT {}",
+            self.quote(constraint_name)?
+        ))
+    }
+
+    /// Postgres names the primary key constraint `<table>_pkey`, unless it
+    /// was named explicitly.
+    fn drop_primary_key(&self, table_name: &str, constraint_name: Option<&str>) -> Result<String> {
+        let constraint_name = match constraint_name {
+            Some(n) => n.to_string(),
+            None => format!("{}_pkey", table_name),
+        };
+        Ok(format!("DROP CONSTRAINT {}", self.quote(&constraint_name)?))
     }
 
-    fn drop_table(&self, name: &str) -> String {
-        format!("DROP TABLE {}.\"{}\";", self.schema, name,)
+    fn rename_constraint(
+        &self,
+        constraint_name: &str,
+        new_constraint_name: &str,
+    ) -> Result<String> {
+        Ok(format!(
+            "RENAME CONSTRAINT {} TO {}",
+            self.quote(constraint_name)?,
+            self.quote(new_constraint_name)?
+        ))
     }
 
     fn add_column(
@@ -69,129 +408,786 @@ impl SqlDialect for Postgres {
         with_prefix: bool,
         ct: &ColumnType,
         constraints: &Constraints,
-    ) -> String {
-        format!(
-            "{}\"{}\" {}{}",
-            with_prefix.then(|| "ADD COLUMN ").unwrap_or(""),
-            name,
-            self.column_type(ct),
-            self.constraints(constraints)
-        )
+    ) -> Result<String> {
+        let serial = match ct {
+            ColumnType::INTEGER => Some(ColumnType::SERIAL),
+            ColumnType::BIGINT => Some(ColumnType::BIGSERIAL),
+            _ => None,
+        };
+        if let Some(serial) =
+            serial.filter(|_| constraints.identity && self.auto_increment == AutoIncrement::Serial)
+        {
+            let mut constraints = constraints.clone();
+            constraints.identity = false;
+
+            return self.add_column(name, with_prefix, &serial, &constraints);
+        }
+
+        Ok(format!(
+            "{}{} {}{}",
+            if with_prefix { "ADD COLUMN " } else { "" },
+            self.quote(name)?,
+            self.column_type(ct)?,
+            self.constraints(constraints)?
+        ))
     }
 
-    fn rename_column(&self, name: &str, new_name: &str) -> String {
-        format!("RENAME COLUMN \"{}\" TO \"{}\"", name, new_name)
+    fn rename_column(
+        &self,
+        table_name: &str,
+        name: &str,
+        new_name: &str,
+        if_exists: bool,
+    ) -> Result<String> {
+        self.alter_table(
+            table_name,
+            vec![format!(
+                "RENAME COLUMN {} TO {}",
+                self.quote(name)?,
+                self.quote(new_name)?
+            )],
+            if_exists,
+        )
     }
 
-    fn alter_column(&self, name: &str, ct: &ColumnType, conversion_method: Option<&str>) -> String {
-        format!(
-            "ALTER COLUMN \"{}\" TYPE {}{}",
-            name,
-            self.column_type(ct),
+    fn alter_column(
+        &self,
+        name: &str,
+        ct: &ColumnType,
+        conversion_method: Option<&str>,
+    ) -> Result<String> {
+        Ok(format!(
+            "ALTER COLUMN {} TYPE {}{}",
+            self.quote(name)?,
+            self.column_type(ct)?,
             conversion_method
                 .map(|u| format!(" USING {}", u))
                 .unwrap_or_else(|| "".into())
-        )
+        ))
     }
 
-    fn drop_column(&self, name: &str, if_exists: bool) -> String {
-        format!(
-            "DROP COLUMN {}\"{}\"",
-            if_exists.then(|| "IF EXISTS ").unwrap_or(""),
-            name
-        )
+    fn drop_column(&self, name: &str, if_exists: bool) -> Result<String> {
+        Ok(format!(
+            "DROP COLUMN {}{}",
+            if if_exists { "IF EXISTS " } else { "" },
+            self.quote(name)?
+        ))
+    }
+
+    fn set_column_storage(&self, name: &str, storage: &Storage) -> Result<String> {
+        Ok(format!(
+            "ALTER COLUMN {} SET STORAGE {}",
+            self.quote(name)?,
+            match storage {
+                Storage::Plain => "PLAIN",
+                Storage::External => "EXTERNAL",
+                Storage::Extended => "EXTENDED",
+                Storage::Main => "MAIN",
+            }
+        ))
+    }
+
+    fn set_column_compression(&self, name: &str, compression: &str) -> Result<String> {
+        self.require_version(14, "Column compression")?;
+        Ok(format!(
+            "ALTER COLUMN {} SET COMPRESSION {}",
+            self.quote(name)?,
+            compression
+        ))
     }
 
     fn add_index(
         &self,
-        _table_name: &str,
-        _columns: &[String],
-        _idx_name: &Option<String>,
-    ) -> String {
-        todo!()
+        table_name: &str,
+        columns: &[IndexColumn],
+        idx_name: &Option<String>,
+        options: &IndexOptions,
+    ) -> Result<String> {
+        if options.if_not_exists && idx_name.is_none() {
+            return Err(SqlPressError::invalid(
+                "IF NOT EXISTS requires a name for the index",
+            ));
+        }
+        Ok(format!(
+            "CREATE {}INDEX {}{}{}ON {}{}{} ({}){}{}{}{};",
+            if options.unique { "UNIQUE " } else { "" },
+            if options.concurrently {
+                "CONCURRENTLY "
+            } else {
+                ""
+            },
+            if options.if_not_exists {
+                "IF NOT EXISTS "
+            } else {
+                ""
+            },
+            match idx_name {
+                Some(x) => format!("{} ", self.quote(x)?),
+                None => "".into(),
+            },
+            if options.only { "ONLY " } else { "" },
+            self.qualified(table_name)?,
+            match options.method {
+                None => "",
+                Some(IndexMethod::BTree) => " USING btree",
+                Some(IndexMethod::Hash) => " USING hash",
+                Some(IndexMethod::Gist) => " USING gist",
+                Some(IndexMethod::Gin) => " USING gin",
+                Some(IndexMethod::Brin) => " USING brin",
+            },
+            self.index_columns(columns)?,
+            if options.include.is_empty() {
+                "".into()
+            } else {
+                format!(" INCLUDE ({})", self.quote_all(&options.include)?)
+            },
+            if options.storage_parameters.is_empty() {
+                "".into()
+            } else {
+                format!(
+                    " WITH ({})",
+                    options
+                        .storage_parameters
+                        .iter()
+                        .map(|(k, v)| format!("{} = {}", k, v))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            },
+            match &options.tablespace {
+                Some(t) => format!(" TABLESPACE {}", self.quote(t)?),
+                None => "".into(),
+            },
+            match &options.where_clause {
+                Some(c) => format!(" WHERE {}", self.expr(c)?),
+                None => "".into(),
+            }
+        ))
     }
 
     fn add_foreign_index(
         &self,
-        column_name: &str,
+        columns: &[String],
         foreign_table_name: &str,
-        foreign_column_name: &str,
+        foreign_columns: &[String],
         idx_name: Option<String>,
         add_clause: &bool,
-    ) -> String {
-        format!(
-            "{}{}FOREIGN KEY(\"{}\") REFERENCES \"{}\"(\"{}\")",
-            add_clause
-                .then(|| format!("ADD "))
-                .unwrap_or_else(|| "".into()),
-            idx_name
-                .map(|x| format!("CONSTRAINT {} ", x))
-                .unwrap_or_else(|| "".into()),
-            column_name,
-            foreign_table_name,
-            foreign_column_name
-        )
+        options: &ForeignKeyOptions,
+    ) -> Result<String> {
+        if !*add_clause && options.not_valid {
+            return Err(SqlPressError::invalid(
+                "NOT VALID is only supported for constraints added to an existing table",
+            ));
+        }
+        Ok(format!(
+            "{}{}FOREIGN KEY({}) REFERENCES {}({}){}{}{}",
+            if *add_clause { "ADD " } else { "" },
+            match idx_name {
+                Some(x) => format!("CONSTRAINT {} ", self.quote(&x)?),
+                None => "".into(),
+            },
+            self.quote_all(columns)?,
+            self.qualified(foreign_table_name)?,
+            self.quote_all(foreign_columns)?,
+            match options.match_type {
+                Some(ForeignKeyMatch::Full) => " MATCH FULL",
+                Some(ForeignKeyMatch::Partial) => {
+                    return Err(SqlPressError::unsupported(
+                        "Postgres does not implement MATCH PARTIAL",
+                    ));
+                }
+                Some(ForeignKeyMatch::Simple) => " MATCH SIMPLE",
+                None => "",
+            },
+            self.referential_actions(options),
+            if options.not_valid { " NOT VALID" } else { "" }
+        ))
     }
 
-    fn add_primary_index(&self, columns: &Vec<String>) -> String {
-        format!(
-            "PRIMARY KEY({})",
-            columns
+    fn add_primary_index(
+        &self,
+        constraint_name: Option<&str>,
+        columns: &[String],
+        add_clause: bool,
+    ) -> Result<String> {
+        Ok(format!(
+            "{}{}PRIMARY KEY({})",
+            if add_clause { "ADD " } else { "" },
+            match constraint_name {
+                Some(x) => format!("CONSTRAINT {} ", self.quote(x)?),
+                None => "".into(),
+            },
+            self.quote_all(columns)?
+        ))
+    }
+
+    fn add_unique_constraint(
+        &self,
+        constraint_name: &str,
+        columns: &[String],
+        nulls_not_distinct: bool,
+        add_clause: bool,
+    ) -> Result<String> {
+        if columns.len() < 2 {
+            return Err(SqlPressError::invalid("This only supports multi-column unique contrainst. For single columns, please just use the .unique() function"));
+        }
+        Ok(format!(
+            "{}CONSTRAINT {} UNIQUE {}({})",
+            if add_clause { "ADD " } else { "" },
+            self.quote(constraint_name)?,
+            self.nulls_not_distinct(nulls_not_distinct)?,
+            self.quote_all(columns)?
+        ))
+    }
+
+    fn add_unique_constraint_using_index(
+        &self,
+        constraint_name: &str,
+        index_name: &str,
+    ) -> Result<String> {
+        Ok(format!(
+            "ADD CONSTRAINT {} UNIQUE USING INDEX {}",
+            self.quote(constraint_name)?,
+            self.quote(index_name)?
+        ))
+    }
+
+    fn add_primary_index_using_index(&self, index_name: &str) -> Result<String> {
+        Ok(format!(
+            "ADD PRIMARY KEY USING INDEX {}",
+            self.quote(index_name)?
+        ))
+    }
+
+    fn drop_unique_constraint(&self, constraint_name: &str) -> Result<String> {
+        Ok(format!("DROP CONSTRAINT {}", self.quote(constraint_name)?))
+    }
+
+    fn add_check_constraint(
+        &self,
+        constraint_name: &str,
+        condition: &Expr,
+        add_clause: bool,
+        not_valid: bool,
+    ) -> Result<String> {
+        if !add_clause && not_valid {
+            return Err(SqlPressError::invalid(
+                "NOT VALID is only supported for constraints added to an existing table",
+            ));
+        }
+        Ok(format!(
+            "{}CONSTRAINT {} CHECK ({}){}",
+            if add_clause { "ADD " } else { "" },
+            self.quote(constraint_name)?,
+            self.expr(condition)?,
+            if not_valid { " NOT VALID" } else { "" }
+        ))
+    }
+
+    fn drop_check_constraint(&self, constraint_name: &str) -> Result<String> {
+        Ok(format!("DROP CONSTRAINT {}", self.quote(constraint_name)?))
+    }
+
+    fn drop_constraint(
+        &self,
+        constraint_name: &str,
+        if_exists: bool,
+        cascade: bool,
+    ) -> Result<String> {
+        Ok(format!(
+            "DROP CONSTRAINT {}{}{}",
+            if if_exists { "IF EXISTS " } else { "" },
+            self.quote(constraint_name)?,
+            if cascade { " CASCADE" } else { "" }
+        ))
+    }
+
+    fn drop_index(
+        &self,
+        _table_name: Option<&str>,
+        index_name: &str,
+        if_exists: bool,
+        concurrently: bool,
+    ) -> Result<String> {
+        Ok(format!(
+            "DROP INDEX {}{}{};",
+            if concurrently { "CONCURRENTLY " } else { "" },
+            if if_exists { "IF EXISTS " } else { "" },
+            self.qualified(index_name)?
+        ))
+    }
+
+    fn rename_index(&self, index_name: &str, new_index_name: &str) -> Result<String> {
+        Ok(format!(
+            "ALTER INDEX {} RENAME TO {};",
+            self.qualified(index_name)?,
+            self.quote(new_index_name)?
+        ))
+    }
+
+    fn attach_index_partition(
+        &self,
+        index_name: &str,
+        partition_index_name: &str,
+    ) -> Result<String> {
+        Ok(format!(
+            "ALTER INDEX {} ATTACH PARTITION {};",
+            self.qualified(index_name)?,
+            self.qualified(partition_index_name)?
+        ))
+    }
+
+    fn reindex_index(&self, index_name: &str, concurrently: bool) -> Result<String> {
+        Ok(format!(
+            "REINDEX INDEX {}{};",
+            if concurrently { "CONCURRENTLY " } else { "" },
+            self.qualified(index_name)?
+        ))
+    }
+
+    fn reindex_table(&self, table_name: &str) -> Result<String> {
+        Ok(format!("REINDEX TABLE {};", self.qualified(table_name)?))
+    }
+
+    fn replace_primary_key_concurrently(
+        &self,
+        table_name: &str,
+        columns: &[String],
+        constraint_name: Option<&str>,
+    ) -> Result<String> {
+        let constraint_name = match constraint_name {
+            Some(n) => n.to_string(),
+            None => format!("{}_pkey", table_name),
+        };
+        let index_name = format!("{}_new", constraint_name);
+
+        Ok(format!(
+            "CREATE UNIQUE INDEX CONCURRENTLY {} ON {} ({});\n\n{}",
+            self.quote(&index_name)?,
+            self.qualified(table_name)?,
+            self.quote_all(columns)?,
+            self.alter_table(
+                table_name,
+                vec![
+                    format!("DROP CONSTRAINT {}", self.quote(&constraint_name)?),
+                    format!(
+                        "ADD CONSTRAINT {} PRIMARY KEY USING INDEX {}",
+                        self.quote(&constraint_name)?,
+                        self.quote(&index_name)?
+                    ),
+                ],
+                false
+            )?
+        ))
+    }
+
+    fn rename_view(&self, name: &str, new_view_name: &str) -> Result<String> {
+        Ok(format!(
+            "ALTER VIEW {} RENAME TO {};",
+            self.qualified(name)?,
+            self.quote(new_view_name)?
+        ))
+    }
+
+    fn alter_view_owner(&self, name: &str, role: &str) -> Result<String> {
+        Ok(format!(
+            "ALTER VIEW {} OWNER TO {};",
+            self.qualified(name)?,
+            self.quote(role)?
+        ))
+    }
+
+    fn alter_view_set_options(&self, name: &str, options: &[(String, String)]) -> Result<String> {
+        Ok(format!(
+            "ALTER VIEW {} SET ({});",
+            self.qualified(name)?,
+            options
                 .iter()
-                .map(|c| format!("\"{}\"", c))
+                .map(|(k, v)| format!("{} = {}", k, v))
                 .collect::<Vec<String>>()
                 .join(", ")
-        )
+        ))
+    }
+
+    fn create_extension(&self, name: &str) -> Result<String> {
+        Ok(format!(
+            "CREATE EXTENSION IF NOT EXISTS {};",
+            self.quote(name)?
+        ))
+    }
+
+    fn ensure_uuid_generation(&self) -> Result<String> {
+        Ok(match self.uuid_generation() {
+            UuidGeneration::Builtin => "".into(),
+            UuidGeneration::Pgcrypto => "CREATE EXTENSION IF NOT EXISTS \"pgcrypto\";".into(),
+            UuidGeneration::UuidOssp => "CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\";".into(),
+        })
+    }
+
+    fn insert_rows(
+        &self,
+        table_name: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+    ) -> Result<String> {
+        Ok(format!(
+            "INSERT INTO {} ({}) VALUES\n{};",
+            self.qualified(table_name)?,
+            self.quote_all(columns)?,
+            rows.iter()
+                .map(|r| {
+                    Ok(format!(
+                        "({})",
+                        r.iter()
+                            .map(|v| self.expr(&Expr::Value(v.clone())))
+                            .collect::<Result<Vec<String>>>()?
+                            .join(", ")
+                    ))
+                })
+                .collect::<Result<Vec<String>>>()?
+                .join(",\n")
+        ))
+    }
+
+    fn lock_timeout(&self, timeout: Duration) -> Result<String> {
+        Ok(format!(
+            "SET LOCAL lock_timeout = '{}ms';",
+            timeout.as_millis()
+        ))
+    }
+
+    /// `SET LOCAL` ends with the transaction.
+    fn reset_lock_timeout(&self) -> Result<String> {
+        Ok("".into())
+    }
+
+    fn version_trigger(&self, table_name: &str) -> Result<String> {
+        let function = format!("{}_version_trigger", table_name);
+
+        Ok(format!(
+            "CREATE FUNCTION {}() RETURNS trigger AS $sql_press$\nBEGIN\nNEW.\"version\" := OLD.\"version\" + 1;\nRETURN NEW;\nEND;\n$sql_press$ LANGUAGE plpgsql;\n\nCREATE TRIGGER {} BEFORE UPDATE ON {}\nFOR EACH ROW EXECUTE FUNCTION {}();",
+            self.qualified(&function)?,
+            self.quote(&function)?,
+            self.qualified(table_name)?,
+            self.qualified(&function)?
+        ))
     }
 
-    fn add_unique_constraint(&self, constraint_name: &str, columns: &Vec<String>) -> String {
-        assert!(columns.len() > 1, "This only supports multi-column unique contrainst. For single columns, please just use the .unique() function");
-        format!(
-            r#"CONSTRAINT "{}" UNIQUE ({})"#,
-            constraint_name,
+    fn audit_history(&self, table_name: &str, columns: &[(String, ColumnType)]) -> Result<String> {
+        let history = format!("{}_history", table_name);
+        let function = format!("{}_history_trigger", table_name);
+
+        let mut changed_at = Constraints::new();
+        changed_at.not_null = true;
+        changed_at.default = DefaultConstraint::Expr(Expr::func("now", Vec::new()));
+        let mut changed_by = Constraints::new();
+        changed_by.not_null = true;
+        changed_by.default = DefaultConstraint::Expr(Expr::raw("current_user"));
+        let mut operation = Constraints::new();
+        operation.not_null = true;
+
+        let table = self.create_table(
+            &history,
             columns
                 .iter()
-                .map(|c| format!("\"{}\"", c))
-                .collect::<Vec<String>>()
+                .map(|(n, ct)| {
+                    // the history records values, it must not generate them
+                    let ct = match ct {
+                        ColumnType::SERIAL => &ColumnType::INTEGER,
+                        ColumnType::BIGSERIAL => &ColumnType::BIGINT,
+                        ct => ct,
+                    };
+                    self.add_column(n, false, ct, &Constraints::new())
+                })
+                .chain([
+                    self.add_column(
+                        "operation",
+                        false,
+                        &ColumnType::VARCHAR(Some(6)),
+                        &operation,
+                    ),
+                    self.add_column(
+                        "changed_at",
+                        false,
+                        &ColumnType::TIMESTAMPTZ(None),
+                        &changed_at,
+                    ),
+                    self.add_column(
+                        "changed_by",
+                        false,
+                        &ColumnType::VARCHAR(Some(128)),
+                        &changed_by,
+                    ),
+                ])
+                .collect::<Result<Vec<String>>>()?,
+            false,
+        )?;
+
+        let names = columns
+            .iter()
+            .map(|(n, _)| n.clone())
+            .collect::<Vec<String>>();
+        let insert = |row: &str| -> Result<String> {
+            Ok(format!(
+                "INSERT INTO {} ({}, \"operation\") VALUES ({}, TG_OP);",
+                self.qualified(&history)?,
+                self.quote_all(&names)?,
+                names
+                    .iter()
+                    .map(|n| Ok(format!("{}.{}", row, self.quote(n)?)))
+                    .collect::<Result<Vec<String>>>()?
+                    .join(", ")
+            ))
+        };
+
+        Ok(format!(
+            "{}\n\nCREATE FUNCTION {}() RETURNS trigger AS $sql_press$\nBEGIN\nIF TG_OP = 'DELETE' THEN\n{}\nRETURN OLD;\nEND IF;\n{}\nRETURN NEW;\nEND;\n$sql_press$ LANGUAGE plpgsql;\n\nCREATE TRIGGER {} AFTER INSERT OR UPDATE OR DELETE ON {}\nFOR EACH ROW EXECUTE FUNCTION {}();",
+            table,
+            self.qualified(&function)?,
+            insert("OLD")?,
+            insert("NEW")?,
+            self.quote(&function)?,
+            self.qualified(table_name)?,
+            self.qualified(&function)?
+        ))
+    }
+
+    /// Renders an anonymous code block, which checks the catalog before
+    /// executing the DDL. The dollar quote tag is chosen so that it doesn't
+    /// occur within the DDL itself, e.g. in nested function bodies.
+    fn only_if(&self, precondition: &Precondition, ddl: &str) -> Result<String> {
+        let (negate, table, column) = match precondition {
+            Precondition::TableExists(t) => ("", t, None),
+            Precondition::TableMissing(t) => ("NOT ", t, None),
+            Precondition::ColumnExists(t, c) => ("", t, Some(c)),
+            Precondition::ColumnMissing(t, c) => ("NOT ", t, Some(c)),
+        };
+        let query = match column {
+            None => format!(
+                "SELECT 1 FROM information_schema.tables WHERE table_schema = {} AND table_name = {}",
+                self.literal(&self.schema),
+                self.literal(table)
+            ),
+            Some(c) => format!(
+                "SELECT 1 FROM information_schema.columns WHERE table_schema = {} AND table_name = {} AND column_name = {}",
+                self.literal(&self.schema),
+                self.literal(table),
+                self.literal(c)
+            ),
+        };
+        let mut tag = "$sql_press$".to_string();
+        let mut i = 0;
+        while ddl.contains(&tag) {
+            i += 1;
+            tag = format!("$sql_press_{}$", i);
+        }
+
+        Ok(format!(
+            "DO {}\nBEGIN\nIF {}EXISTS ({}) THEN\n{}\nEND IF;\nEND\n{};",
+            tag,
+            negate,
+            query,
+            ddl.trim_end(),
+            tag
+        ))
+    }
+
+    fn create_composite_type(&self, name: &str, fields: &[(String, ColumnType)]) -> Result<String> {
+        Ok(format!(
+            "CREATE TYPE {} AS (\n{}\n);",
+            self.qualified(name)?,
+            fields
+                .iter()
+                .map(|(n, ct)| Ok(format!("{} {}", self.quote(n)?, self.column_type(ct)?)))
+                .collect::<Result<Vec<String>>>()?
+                .join(",\n")
+        ))
+    }
+
+    fn create_enum_type(&self, name: &str, values: &[String]) -> Result<String> {
+        Ok(format!(
+            "CREATE TYPE {} AS ENUM ({});",
+            self.qualified(name)?,
+            values
+                .iter()
+                .map(|v| self.expr(&Expr::val(v.as_str())))
+                .collect::<Result<Vec<String>>>()?
                 .join(", ")
-        )
+        ))
+    }
+
+    fn create_domain(
+        &self,
+        name: &str,
+        ct: &ColumnType,
+        not_null: bool,
+        checks: &[String],
+    ) -> Result<String> {
+        Ok(format!(
+            "CREATE DOMAIN {} AS {}{}{};",
+            self.qualified(name)?,
+            self.column_type(ct)?,
+            if not_null { " NOT NULL" } else { "" },
+            checks
+                .iter()
+                .map(|c| format!(" CHECK ({})", c))
+                .collect::<String>()
+        ))
+    }
+
+    fn drop_domain(&self, name: &str) -> Result<String> {
+        Ok(format!("DROP DOMAIN {};", self.qualified(name)?))
+    }
+
+    fn add_enum_value(
+        &self,
+        name: &str,
+        value: &str,
+        position: &Option<EnumValuePosition>,
+    ) -> Result<String> {
+        Ok(format!(
+            "ALTER TYPE {} ADD VALUE IF NOT EXISTS {}{};",
+            self.qualified(name)?,
+            self.expr(&Expr::val(value))?,
+            match position {
+                Some(EnumValuePosition::Before(v)) =>
+                    format!(" BEFORE {}", self.expr(&Expr::val(v.as_str()))?),
+                Some(EnumValuePosition::After(v)) =>
+                    format!(" AFTER {}", self.expr(&Expr::val(v.as_str()))?),
+                None => "".into(),
+            }
+        ))
     }
 
-    fn column_type(&self, ct: &ColumnType) -> String {
-        match ct {
+    fn rename_type(&self, name: &str, new_type_name: &str) -> Result<String> {
+        Ok(format!(
+            "ALTER TYPE {} RENAME TO {};",
+            self.qualified(name)?,
+            self.quote(new_type_name)?
+        ))
+    }
+
+    fn drop_type(&self, name: &str) -> Result<String> {
+        Ok(format!("DROP TYPE {};", self.qualified(name)?))
+    }
+
+    fn expr(&self, expr: &Expr) -> Result<String> {
+        expr.render(&ExprStyle {
+            quote: ('"', '"'),
+            true_literal: "TRUE",
+            false_literal: "FALSE",
+        })
+    }
+
+    fn column_type(&self, ct: &ColumnType) -> Result<String> {
+        if let Some(t) = self.type_mapping.as_ref().and_then(|m| m.map_type(ct)) {
+            return Ok(t);
+        }
+
+        Ok(match ct {
             ColumnType::UUID => "uuid".into(),
             ColumnType::BOOL => "boolean".into(),
-            ColumnType::VARCHAR(s) => format!("VARCHAR({})", s),
+            ColumnType::VARCHAR(Some(s)) => format!("VARCHAR({})", s),
+            ColumnType::VARCHAR(None) => match self.unbounded_varchar {
+                UnboundedVarchar::Native => "VARCHAR".into(),
+                UnboundedVarchar::Length(s) => format!("VARCHAR({})", s),
+                UnboundedVarchar::Reject => {
+                    return Err(SqlPressError::invalid(
+                        "VARCHAR columns require an explicit length",
+                    ))
+                }
+            },
             ColumnType::REAL => "real".into(),
             ColumnType::TEXT => "text".into(),
-            ColumnType::TIMESTAMP => "timestamp".into(),
-            ColumnType::TIMESTAMPTZ => "timestamp with time zone".into(),
+            ColumnType::TIMESTAMP(p) => format!("timestamp{}", precision(p)),
+            ColumnType::TIMESTAMPTZ(p) => format!("timestamp{} with time zone", precision(p)),
+            ColumnType::TIME(p) => format!("time{}", precision(p)),
+            ColumnType::TIMETZ(p) => format!("time{} with time zone", precision(p)),
+            ColumnType::SMALLINT => "smallint".into(),
             ColumnType::INTEGER => "integer".into(),
+            ColumnType::BIGINT => "bigint".into(),
+            ColumnType::SERIAL => "serial".into(),
+            ColumnType::BIGSERIAL => "bigserial".into(),
             ColumnType::JSONB => "jsonb".into(),
-        }
+            ColumnType::JSON => "json".into(),
+            ColumnType::TSVECTOR => "tsvector".into(),
+            ColumnType::TSQUERY => "tsquery".into(),
+            ColumnType::CITEXT => "citext".into(),
+            ColumnType::HSTORE => "hstore".into(),
+            ColumnType::LTREE => "ltree".into(),
+            ColumnType::XML => "xml".into(),
+            ColumnType::BINARY => "bytea".into(),
+            ColumnType::Composite(name) => self.qualified(name)?,
+            ColumnType::Domain(name) => self.qualified(name)?,
+            ColumnType::Enum { name, .. } => self.qualified(name)?,
+            ColumnType::Custom(t) => t.clone(),
+        })
     }
 
-    fn constraints(&self, constraints: &Constraints) -> String {
-        let def_constraint = || match &constraints.default {
+    fn constraints(&self, constraints: &Constraints) -> Result<String> {
+        let def_constraint = match &constraints.default {
             crate::column::DefaultConstraint::None => "".into(),
             crate::column::DefaultConstraint::Plain(s) => format!("DEFAULT {}", s),
+            crate::column::DefaultConstraint::Expr(e) => format!("DEFAULT {}", self.expr(e)?),
+            crate::column::DefaultConstraint::CurrentTimestamp => "DEFAULT now()".into(),
+            crate::column::DefaultConstraint::RandomUuid => match self.uuid_generation() {
+                UuidGeneration::UuidOssp => "DEFAULT uuid_generate_v4()".into(),
+                _ => "DEFAULT gen_random_uuid()".into(),
+            },
+        };
+        let unique = if constraints.unique {
+            let name = match &constraints.unique_name {
+                Some(n) => format!("CONSTRAINT {} ", self.quote(n)?),
+                None => "".into(),
+            };
+            let nulls = self.nulls_not_distinct(constraints.nulls_not_distinct)?;
+            format!("{}UNIQUE {}", name, nulls).trim_end().to_string()
+        } else {
+            "".into()
+        };
+        let check = match &constraints.check {
+            Some(e) => format!("CHECK ({})", self.expr(e)?),
+            None => "".into(),
+        };
+        let generated = match &constraints.generated {
+            Some(e) => format!("GENERATED ALWAYS AS ({}) STORED", self.expr(e)?),
+            None => "".into(),
         };
 
-        let c = vec![
-            constraints.primary.then(|| "PRIMARY KEY").unwrap_or(""),
-            constraints.not_null.then(|| "NOT NULL").unwrap_or(""),
-            constraints.unique.then(|| "UNIQUE").unwrap_or(""),
-            def_constraint().as_ref(),
+        let c = [
+            generated.as_ref(),
+            if constraints.identity {
+                "GENERATED BY DEFAULT AS IDENTITY"
+            } else {
+                ""
+            },
+            if constraints.primary {
+                "PRIMARY KEY"
+            } else {
+                ""
+            },
+            if constraints.not_null {
+                "NOT NULL"
+            } else if self.format.explicit_null && !constraints.primary && !constraints.identity {
+                "NULL"
+            } else {
+                ""
+            },
+            unique.as_ref(),
+            def_constraint.as_ref(),
+            check.as_ref(),
         ]
+        .iter()
+        .filter(|c| !c.is_empty())
+        .copied()
+        .collect::<Vec<&str>>()
         .join(" ");
 
-        let c = c.trim();
-
         if !c.is_empty() {
             // prefix with a space
-            format!(" {}", c)
+            Ok(format!(" {}", c))
         } else {
-            "".into()
+            Ok("".into())
         }
     }
 }
@@ -205,159 +1201,1112 @@ mod tests {
     #[test]
     fn create_table() {
         let d = Box::new(Postgres::new());
-        let ddl = d.create_table("tag", Vec::new(), false);
-        assert_eq!(ddl, format!("CREATE TABLE public.\"tag\" (\n\n);"));
+        let ddl = d.create_table("tag", Vec::new(), false).unwrap();
+        assert_eq!(ddl, format!("CREATE TABLE \"public\".\"tag\" (\n\n);"));
 
-        let ddl = d.create_table("tag", vec!["CHANGE 1".into(), "CHANGE 2".into()], false);
+        let ddl = d
+            .create_table("tag", vec!["CHANGE 1".into(), "CHANGE 2".into()], false)
+            .unwrap();
         assert_eq!(
             ddl,
-            format!("CREATE TABLE public.\"tag\" (\nCHANGE 1,\nCHANGE 2\n);")
+            format!("CREATE TABLE \"public\".\"tag\" (\nCHANGE 1,\nCHANGE 2\n);")
         );
 
-        let ddl = d.create_table("tag", Vec::new(), true);
+        let ddl = d.create_table("tag", Vec::new(), true).unwrap();
         assert_eq!(
             ddl,
-            format!("CREATE TABLE IF NOT EXISTS public.\"tag\" (\n\n);")
+            format!("CREATE TABLE IF NOT EXISTS \"public\".\"tag\" (\n\n);")
         );
     }
 
     #[test]
     fn rename_table() {
         let d = Box::new(Postgres::new());
-        let ddl = d.rename_table("tags", "tag");
+        let ddl = d.rename_table("tags", "tag").unwrap();
         assert_eq!(
             ddl,
-            format!("ALTER TABLE public.\"tags\" RENAME TO public.\"tag\";")
+            format!("ALTER TABLE \"public\".\"tags\" RENAME TO \"tag\";")
         );
     }
 
     #[test]
     fn alter_table() {
         let d = Box::new(Postgres::new());
-        let ddl = d.alter_table("tags", Vec::new());
-        assert_eq!(ddl, format!("ALTER TABLE public.\"tags\"\n;"));
+        let ddl = d.alter_table("tags", Vec::new(), false).unwrap();
+        assert_eq!(ddl, format!("ALTER TABLE \"public\".\"tags\"\n;"));
 
-        let ddl = d.alter_table("tags", vec!["CHANGE 1".into(), "CHANGE 2".into()]);
+        let ddl = d
+            .alter_table("tags", vec!["CHANGE 1".into(), "CHANGE 2".into()], false)
+            .unwrap();
         assert_eq!(
             ddl,
-            format!("ALTER TABLE public.\"tags\"\nCHANGE 1,\nCHANGE 2;")
+            format!("ALTER TABLE \"public\".\"tags\"\nCHANGE 1,\nCHANGE 2;")
         );
+
+        let ddl = d
+            .alter_table("tags", vec!["CHANGE 1".into()], true)
+            .unwrap();
+        assert_eq!(ddl, "ALTER TABLE IF EXISTS \"public\".\"tags\"\nCHANGE 1;");
     }
 
     #[test]
     fn drop_table() {
         let d = Box::new(Postgres::new());
-        let ddl = d.drop_table("tags");
-        assert_eq!(ddl, format!("DROP TABLE public.\"tags\";"));
+        let ddl = d.drop_table("tags", false, false).unwrap();
+        assert_eq!(ddl, format!("DROP TABLE \"public\".\"tags\";"));
+
+        let ddl = d.drop_table("tags", true, true).unwrap();
+        assert_eq!(ddl, "DROP TABLE IF EXISTS \"public\".\"tags\" CASCADE;");
     }
 
     #[test]
-    fn add_column() {
+    fn drop_tables() {
         let d = Box::new(Postgres::new());
-        let ddl = d.add_column("id", false, &ColumnType::UUID, &Constraints::new());
-        assert_eq!(ddl, format!("\"id\" uuid"));
-
-        let ddl = d.add_column("id", true, &ColumnType::UUID, &Constraints::new());
-        assert_eq!(ddl, format!("ADD COLUMN \"id\" uuid"));
-
-        let mut constraints = Constraints::new();
-        constraints.primary = true;
-        let ddl = d.add_column("id", true, &ColumnType::UUID, &constraints);
-        assert_eq!(ddl, format!("ADD COLUMN \"id\" uuid PRIMARY KEY"));
-
-        constraints.primary = false;
-        constraints.not_null = true;
-        constraints.unique = true;
-
-        let ddl = d.add_column("id", true, &ColumnType::UUID, &constraints);
-        assert_eq!(ddl, format!("ADD COLUMN \"id\" uuid NOT NULL UNIQUE"));
+        let ddl = d.drop_tables(&["a".into(), "b".into()], false).unwrap();
+        assert_eq!(ddl, "DROP TABLE \"public\".\"a\", \"public\".\"b\";");
 
-        let mut constraints = Constraints::new();
-        constraints.default = DefaultConstraint::Plain("uuid_v4_generate()".into());
-
-        let ddl = d.add_column("id", true, &ColumnType::UUID, &constraints);
+        let ddl = d.drop_tables(&["a".into(), "b".into()], true).unwrap();
         assert_eq!(
             ddl,
-            format!("ADD COLUMN \"id\" uuid DEFAULT uuid_v4_generate()")
+            "DROP TABLE \"public\".\"a\", \"public\".\"b\" CASCADE;"
         );
     }
 
     #[test]
-    fn rename_column() {
+    fn attach_partition() {
         let d = Box::new(Postgres::new());
-        let ddl = d.rename_column("id", "id2");
-        assert_eq!(ddl, format!("RENAME COLUMN \"id\" TO \"id2\""));
+        assert_eq!(
+            d.attach_partition(
+                "orders",
+                "orders_eu",
+                &PartitionBound::List(vec![Expr::val("de"), Expr::val("fr")])
+            ).unwrap(),
+            "ALTER TABLE \"public\".\"orders\" ATTACH PARTITION \"public\".\"orders_eu\" FOR VALUES IN ('de', 'fr');"
+        );
+        assert_eq!(
+            d.attach_partition(
+                "orders",
+                "orders_p0",
+                &PartitionBound::Hash {
+                    modulus: 4,
+                    remainder: 0
+                }
+            ).unwrap(),
+            "ALTER TABLE \"public\".\"orders\" ATTACH PARTITION \"public\".\"orders_p0\" FOR VALUES WITH (MODULUS 4, REMAINDER 0);"
+        );
+        assert_eq!(
+            d.attach_partition("orders", "orders_other", &PartitionBound::Default)
+                .unwrap(),
+            "ALTER TABLE \"public\".\"orders\" ATTACH PARTITION \"public\".\"orders_other\" DEFAULT;"
+        );
+        assert_eq!(
+            d.detach_partition("orders", "orders_eu", false).unwrap(),
+            "ALTER TABLE \"public\".\"orders\" DETACH PARTITION \"public\".\"orders_eu\";"
+        );
     }
 
     #[test]
-    fn drop_column() {
-        let d = Box::new(Postgres::new());
-        let ddl = d.drop_column("id", false);
-        assert_eq!(ddl, format!("DROP COLUMN \"id\""));
-
-        let ddl = d.drop_column("id", true);
-        assert_eq!(ddl, format!("DROP COLUMN IF EXISTS \"id\""));
+    fn detach_partition_concurrently_version() {
+        assert_eq!(
+            Postgres::new()
+                .with_version(13)
+                .detach_partition("orders", "orders_eu", true),
+            Err(SqlPressError::UnsupportedByDialect(
+                "DETACH PARTITION CONCURRENTLY requires postgres 14".into()
+            ))
+        );
     }
 
     #[test]
-    fn add_foreign_index() {
-        let d = Box::new(Postgres::new());
-        let ddl = d.add_foreign_index("blubb_id", "blubb", "id", None, &false);
+    fn temporal_precision() {
+        let d = Postgres::new();
         assert_eq!(
-            ddl,
-            format!("FOREIGN KEY(\"blubb_id\") REFERENCES \"blubb\"(\"id\")")
+            d.column_type(&ColumnType::TIMESTAMP(None)).unwrap(),
+            "timestamp"
         );
-
+        assert_eq!(
+            d.column_type(&ColumnType::TIMESTAMP(Some(6))).unwrap(),
+            "timestamp(6)"
+        );
+        assert_eq!(
+            d.column_type(&ColumnType::TIMESTAMPTZ(Some(3))).unwrap(),
+            "timestamp(3) with time zone"
+        );
+        assert_eq!(d.column_type(&ColumnType::TIME(None)).unwrap(), "time");
+        assert_eq!(
+            d.column_type(&ColumnType::TIME(Some(0))).unwrap(),
+            "time(0)"
+        );
+        assert_eq!(
+            d.column_type(&ColumnType::TIMETZ(None)).unwrap(),
+            "time with time zone"
+        );
+        assert_eq!(
+            d.column_type(&ColumnType::TIMETZ(Some(3))).unwrap(),
+            "time(3) with time zone"
+        );
+    }
+
+    #[test]
+    fn unbounded_varchar() {
+        let d = Postgres::new();
+        assert_eq!(
+            d.column_type(&ColumnType::VARCHAR(None)).unwrap(),
+            "VARCHAR"
+        );
+        assert_eq!(
+            d.column_type(&ColumnType::VARCHAR(Some(20))).unwrap(),
+            "VARCHAR(20)"
+        );
+
+        let d = Postgres::new().with_unbounded_varchar(UnboundedVarchar::Length(255));
+        assert_eq!(
+            d.column_type(&ColumnType::VARCHAR(None)).unwrap(),
+            "VARCHAR(255)"
+        );
+    }
+
+    #[test]
+    fn unbounded_varchar_reject() {
+        let d = Postgres::new().with_unbounded_varchar(UnboundedVarchar::Reject);
+        assert_eq!(
+            d.column_type(&ColumnType::VARCHAR(None)),
+            Err(SqlPressError::InvalidChange(
+                "VARCHAR columns require an explicit length".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn explicit_null() {
+        let d = Postgres::new().with_format_options(FormatOptions::new().explicit_null(true));
+        let ddl = d
+            .add_column("name", false, &ColumnType::TEXT, &Constraints::new())
+            .unwrap();
+        assert_eq!(ddl, "\"name\" text NULL");
+
+        let mut constraints = Constraints::new();
+        constraints.not_null = true;
+        let ddl = d
+            .add_column("name", false, &ColumnType::TEXT, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "\"name\" text NOT NULL");
+
+        let mut constraints = Constraints::new();
+        constraints.primary = true;
+        let ddl = d
+            .add_column("id", false, &ColumnType::UUID, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "\"id\" uuid PRIMARY KEY");
+    }
+
+    #[test]
+    fn default_expr() {
+        let mut constraints = Constraints::new();
+        constraints.default = DefaultConstraint::Expr(Expr::val(false));
+        let d = Postgres::new();
+        let ddl = d
+            .add_column("active", false, &ColumnType::BOOL, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "\"active\" boolean DEFAULT FALSE");
+    }
+
+    #[test]
+    fn auto_increment_strategy() {
+        let mut constraints = Constraints::new();
+        constraints.primary = true;
+        constraints.identity = true;
+
+        let d = Postgres::new();
+        let ddl = d
+            .add_column("id", false, &ColumnType::INTEGER, &constraints)
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "\"id\" integer GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY"
+        );
+
+        let d = Postgres::new().auto_increment_strategy(AutoIncrement::Serial);
+        let ddl = d
+            .add_column("id", false, &ColumnType::INTEGER, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "\"id\" serial PRIMARY KEY");
+        let ddl = d
+            .add_column("id", false, &ColumnType::BIGINT, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "\"id\" bigserial PRIMARY KEY");
+    }
+
+    #[test]
+    fn column_types() {
+        let d = Postgres::new();
+        assert_eq!(d.column_type(&ColumnType::SMALLINT).unwrap(), "smallint");
+        assert_eq!(d.column_type(&ColumnType::BIGINT).unwrap(), "bigint");
+        assert_eq!(d.column_type(&ColumnType::BINARY).unwrap(), "bytea");
+        assert_eq!(d.column_type(&ColumnType::JSON).unwrap(), "json");
+        assert_eq!(d.column_type(&ColumnType::XML).unwrap(), "xml");
+        assert_eq!(d.column_type(&ColumnType::LTREE).unwrap(), "ltree");
+        assert_eq!(d.column_type(&ColumnType::TSVECTOR).unwrap(), "tsvector");
+        assert_eq!(
+            d.column_type(&ColumnType::Custom("geography(Point,4326)".into()))
+                .unwrap(),
+            "geography(Point,4326)"
+        );
+        let ddl = d
+            .add_column("id", false, &ColumnType::BIGSERIAL, &Constraints::new())
+            .unwrap();
+        assert_eq!(ddl, "\"id\" bigserial");
+    }
+
+    #[test]
+    fn add_column() {
         let d = Box::new(Postgres::new());
-        let ddl = d.add_foreign_index("blubb_id", "blubb", "id", None, &true);
+        let ddl = d
+            .add_column("id", false, &ColumnType::UUID, &Constraints::new())
+            .unwrap();
+        assert_eq!(ddl, format!("\"id\" uuid"));
+
+        let ddl = d
+            .add_column("id", true, &ColumnType::UUID, &Constraints::new())
+            .unwrap();
+        assert_eq!(ddl, format!("ADD COLUMN \"id\" uuid"));
+
+        let mut constraints = Constraints::new();
+        constraints.primary = true;
+        let ddl = d
+            .add_column("id", true, &ColumnType::UUID, &constraints)
+            .unwrap();
+        assert_eq!(ddl, format!("ADD COLUMN \"id\" uuid PRIMARY KEY"));
+
+        constraints.primary = false;
+        constraints.not_null = true;
+        constraints.unique = true;
+
+        let ddl = d
+            .add_column("id", true, &ColumnType::UUID, &constraints)
+            .unwrap();
+        assert_eq!(ddl, format!("ADD COLUMN \"id\" uuid NOT NULL UNIQUE"));
+
+        let mut constraints = Constraints::new();
+        constraints.default = DefaultConstraint::Plain("uuid_v4_generate()".into());
+
+        let ddl = d
+            .add_column("id", true, &ColumnType::UUID, &constraints)
+            .unwrap();
+        assert_eq!(
+            ddl,
+            format!("ADD COLUMN \"id\" uuid DEFAULT uuid_v4_generate()")
+        );
+
+        let mut constraints = Constraints::new();
+        constraints.identity = true;
+        constraints.primary = true;
+
+        let ddl = d
+            .add_column("id", false, &ColumnType::INTEGER, &constraints)
+            .unwrap();
         assert_eq!(
             ddl,
-            format!("ADD FOREIGN KEY(\"blubb_id\") REFERENCES \"blubb\"(\"id\")")
+            "\"id\" integer GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY"
         );
 
-        let ddl = d.add_foreign_index(
-            "blubb_id",
-            "blubb",
-            "id",
-            Some("fk_blubb_blubb_id".into()),
-            &false,
+        let mut constraints = Constraints::new();
+        constraints.not_null = true;
+        constraints.check = Some(Expr::col("price").gt(Expr::val(0)));
+
+        let ddl = d
+            .add_column("price", false, &ColumnType::REAL, &constraints)
+            .unwrap();
+        assert_eq!(ddl, "\"price\" real NOT NULL CHECK (\"price\" > 0)");
+    }
+
+    #[test]
+    fn generated_column() {
+        let d = Box::new(Postgres::new());
+        let mut constraints = Constraints::new();
+        constraints.not_null = true;
+        constraints.generated = Some(Expr::func("lower", vec![Expr::col("email")]));
+
+        let ddl = d
+            .add_column("email_lower", true, &ColumnType::TEXT, &constraints)
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "ADD COLUMN \"email_lower\" text GENERATED ALWAYS AS (lower(\"email\")) STORED NOT NULL"
         );
+    }
+
+    #[test]
+    fn rename_column() {
+        let d = Box::new(Postgres::new());
+        let ddl = d.rename_column("tag", "id", "id2", false).unwrap();
         assert_eq!(
             ddl,
-            format!(
-                "CONSTRAINT fk_blubb_blubb_id FOREIGN KEY(\"blubb_id\") REFERENCES \"blubb\"(\"id\")"
+            "ALTER TABLE \"public\".\"tag\"\nRENAME COLUMN \"id\" TO \"id2\";"
+        );
+    }
+
+    #[test]
+    fn column_storage() {
+        let d = Box::new(Postgres::new());
+        assert_eq!(
+            d.set_column_storage("payload", &Storage::External).unwrap(),
+            "ALTER COLUMN \"payload\" SET STORAGE EXTERNAL"
+        );
+        assert_eq!(
+            d.set_column_compression("payload", "lz4").unwrap(),
+            "ALTER COLUMN \"payload\" SET COMPRESSION lz4"
+        );
+    }
+
+    #[test]
+    fn column_compression_version() {
+        assert_eq!(
+            Postgres::new()
+                .with_version(13)
+                .set_column_compression("payload", "lz4"),
+            Err(SqlPressError::UnsupportedByDialect(
+                "Column compression requires postgres 14".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn drop_column() {
+        let d = Box::new(Postgres::new());
+        let ddl = d.drop_column("id", false).unwrap();
+        assert_eq!(ddl, format!("DROP COLUMN \"id\""));
+
+        let ddl = d.drop_column("id", true).unwrap();
+        assert_eq!(ddl, format!("DROP COLUMN IF EXISTS \"id\""));
+    }
+
+    #[test]
+    fn add_index() {
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .add_index("users", &["email".into()], &None, &IndexOptions::new())
+            .unwrap();
+        assert_eq!(ddl, "CREATE INDEX ON \"public\".\"users\" (\"email\");");
+
+        let mut options = IndexOptions::new();
+        options.unique = true;
+        options.storage_parameters = vec![
+            ("fillfactor".into(), "70".into()),
+            ("deduplicate_items".into(), "off".into()),
+        ];
+        options.tablespace = Some("fast_ssd".into());
+        let ddl = d
+            .add_index(
+                "users",
+                &["tenant_id".into(), "email".into()],
+                &Some("idx_users_email".into()),
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE UNIQUE INDEX \"idx_users_email\" ON \"public\".\"users\" (\"tenant_id\", \"email\") WITH (fillfactor = 70, deduplicate_items = off) TABLESPACE \"fast_ssd\";"
+        );
+
+        let mut options = IndexOptions::new();
+        options.method = Some(IndexMethod::Gin);
+        let ddl = d
+            .add_index(
+                "documents",
+                &["search".into()],
+                &Some("documents_search_idx".into()),
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX \"documents_search_idx\" ON \"public\".\"documents\" USING gin (\"search\");"
+        );
+    }
+
+    #[test]
+    fn add_index_partial() {
+        let d = Box::new(Postgres::new());
+        let mut options = IndexOptions::new();
+        options.unique = true;
+        options.where_clause = Some(Expr::col("deleted_at").is_null());
+        let ddl = d
+            .add_index(
+                "users",
+                &["email".into()],
+                &Some("users_email_uq".into()),
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE UNIQUE INDEX \"users_email_uq\" ON \"public\".\"users\" (\"email\") WHERE \"deleted_at\" IS NULL;"
+        );
+    }
+
+    #[test]
+    fn add_index_covering() {
+        let d = Box::new(Postgres::new());
+        let mut options = IndexOptions::new();
+        options.include = vec!["status".into(), "created_at".into()];
+        options.storage_parameters = vec![("fillfactor".into(), "70".into())];
+        let ddl = d
+            .add_index("orders", &["user_id".into()], &None, &options)
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX ON \"public\".\"orders\" (\"user_id\") INCLUDE (\"status\", \"created_at\") WITH (fillfactor = 70);"
+        );
+    }
+
+    #[test]
+    fn add_index_sorted() {
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .add_index(
+                "orders",
+                &[
+                    IndexColumn::asc("user_id").nulls_first(),
+                    IndexColumn::desc("created_at").nulls_last(),
+                ],
+                &None,
+                &IndexOptions::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX ON \"public\".\"orders\" (\"user_id\" ASC NULLS FIRST, \"created_at\" DESC NULLS LAST);"
+        );
+    }
+
+    #[test]
+    fn add_index_opclass() {
+        let d = Box::new(Postgres::new());
+        let mut options = IndexOptions::new();
+        options.method = Some(IndexMethod::Gin);
+        let ddl = d
+            .add_index(
+                "users",
+                &[IndexColumn::new("name").opclass("gin_trgm_ops")],
+                &None,
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX ON \"public\".\"users\" USING gin (\"name\" gin_trgm_ops);"
+        );
+
+        let ddl = d
+            .add_index(
+                "users",
+                &[IndexColumn::desc("email").opclass("varchar_pattern_ops")],
+                &None,
+                &IndexOptions::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX ON \"public\".\"users\" (\"email\" varchar_pattern_ops DESC);"
+        );
+    }
+
+    #[test]
+    fn add_index_if_not_exists() {
+        let d = Box::new(Postgres::new());
+        let mut options = IndexOptions::new();
+        options.concurrently = true;
+        options.if_not_exists = true;
+        let ddl = d
+            .add_index(
+                "users",
+                &["email".into()],
+                &Some("idx_users_email".into()),
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX CONCURRENTLY IF NOT EXISTS \"idx_users_email\" ON \"public\".\"users\" (\"email\");"
+        );
+    }
+
+    #[test]
+    fn add_index_partitioned() {
+        let d = Box::new(Postgres::new());
+        let mut options = IndexOptions::new();
+        options.only = true;
+        let ddl = d
+            .add_index(
+                "measurement",
+                &["logdate".into()],
+                &Some("measurement_logdate_idx".into()),
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE INDEX \"measurement_logdate_idx\" ON ONLY \"public\".\"measurement\" (\"logdate\");"
+        );
+
+        assert_eq!(
+            d.rename_index("measurement_logdate_idx", "idx_measurement_logdate")
+                .unwrap(),
+            "ALTER INDEX \"public\".\"measurement_logdate_idx\" RENAME TO \"idx_measurement_logdate\";"
+        );
+
+        let ddl = d
+            .attach_index_partition("measurement_logdate_idx", "measurement_y2024_logdate_idx")
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "ALTER INDEX \"public\".\"measurement_logdate_idx\" ATTACH PARTITION \"public\".\"measurement_y2024_logdate_idx\";"
+        );
+    }
+
+    #[test]
+    fn add_foreign_index() {
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                None,
+                &false,
+                &ForeignKeyOptions::new(),
             )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            format!("FOREIGN KEY(\"blubb_id\") REFERENCES \"public\".\"blubb\"(\"id\")")
         );
 
-        let ddl = d.add_foreign_index(
-            "blubb_id",
-            "blubb",
-            "id",
-            Some("fk_blubb_blubb_id".into()),
-            &true,
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                None,
+                &true,
+                &ForeignKeyOptions::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            format!("ADD FOREIGN KEY(\"blubb_id\") REFERENCES \"public\".\"blubb\"(\"id\")")
+        );
+
+        let ddl = d
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                Some("fk_blubb_blubb_id".into()),
+                &false,
+                &ForeignKeyOptions::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            format!(
+                "CONSTRAINT \"fk_blubb_blubb_id\" FOREIGN KEY(\"blubb_id\") REFERENCES \"public\".\"blubb\"(\"id\")"
+            )
         );
+
+        let ddl = d
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                Some("fk_blubb_blubb_id".into()),
+                &true,
+                &ForeignKeyOptions::new(),
+            )
+            .unwrap();
         assert_eq!(
             ddl,
             format!(
-                "ADD CONSTRAINT fk_blubb_blubb_id FOREIGN KEY(\"blubb_id\") REFERENCES \"blubb\"(\"id\")"
+                "ADD CONSTRAINT \"fk_blubb_blubb_id\" FOREIGN KEY(\"blubb_id\") REFERENCES \"public\".\"blubb\"(\"id\")"
             )
         );
     }
 
+    #[test]
+    fn add_foreign_index_match() {
+        let d = Box::new(Postgres::new());
+        let mut options = ForeignKeyOptions::new();
+        options.match_type = Some(ForeignKeyMatch::Full);
+        let ddl = d
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                None,
+                &true,
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "ADD FOREIGN KEY(\"blubb_id\") REFERENCES \"public\".\"blubb\"(\"id\") MATCH FULL"
+        );
+
+        options.match_type = Some(ForeignKeyMatch::Simple);
+        let ddl = d
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                None,
+                &false,
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "FOREIGN KEY(\"blubb_id\") REFERENCES \"public\".\"blubb\"(\"id\") MATCH SIMPLE"
+        );
+
+        options.match_type = Some(ForeignKeyMatch::Partial);
+        assert_eq!(
+            d.add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                None,
+                &false,
+                &options,
+            ),
+            Err(SqlPressError::unsupported(
+                "Postgres does not implement MATCH PARTIAL"
+            ))
+        );
+    }
+
     #[test]
     fn add_primary_index() {
         let d = Box::new(Postgres::new());
-        let ddl = d.add_primary_index(&vec!["id".into(), "id2".into()]);
+        let ddl = d
+            .add_primary_index(None, &["id".into(), "id2".into()], false)
+            .unwrap();
         assert_eq!(ddl, format!("PRIMARY KEY(\"id\", \"id2\")"));
+
+        let ddl = d
+            .add_primary_index(Some("pk_orders"), &["id".into()], true)
+            .unwrap();
+        assert_eq!(ddl, "ADD CONSTRAINT \"pk_orders\" PRIMARY KEY(\"id\")");
     }
 
     #[test]
     fn add_unique_constraint() {
         let d = Box::new(Postgres::new());
-        let ddl = d.add_unique_constraint("id_id2_unique", &vec!["id".into(), "id2".into()]);
+        let ddl = d
+            .add_unique_constraint("id_id2_unique", &["id".into(), "id2".into()], false, false)
+            .unwrap();
         assert_eq!(
             ddl,
             format!("CONSTRAINT \"id_id2_unique\" UNIQUE (\"id\", \"id2\")")
         );
+
+        let ddl = d
+            .add_unique_constraint("id_id2_unique", &["id".into(), "id2".into()], true, true)
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "ADD CONSTRAINT \"id_id2_unique\" UNIQUE NULLS NOT DISTINCT (\"id\", \"id2\")"
+        );
+    }
+
+    #[test]
+    fn add_constraint_using_index() {
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .add_unique_constraint_using_index("uq_users_email", "users_email_idx")
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "ADD CONSTRAINT \"uq_users_email\" UNIQUE USING INDEX \"users_email_idx\""
+        );
+
+        let ddl = d.add_primary_index_using_index("users_id_idx").unwrap();
+        assert_eq!(ddl, "ADD PRIMARY KEY USING INDEX \"users_id_idx\"");
+    }
+
+    #[test]
+    fn drop_unique_constraint() {
+        let d = Box::new(Postgres::new());
+        let ddl = d.drop_unique_constraint("uq_users_email").unwrap();
+        assert_eq!(ddl, "DROP CONSTRAINT \"uq_users_email\"");
+    }
+
+    #[test]
+    fn drop_constraint() {
+        let d = Box::new(Postgres::new());
+        assert_eq!(
+            d.drop_constraint("fk_order_user", false, false).unwrap(),
+            "DROP CONSTRAINT \"fk_order_user\""
+        );
+        assert_eq!(
+            d.drop_constraint("fk_order_user", true, true).unwrap(),
+            "DROP CONSTRAINT IF EXISTS \"fk_order_user\" CASCADE"
+        );
+    }
+
+    #[test]
+    fn add_foreign_index_not_valid() {
+        let mut options = ForeignKeyOptions::new();
+        options.not_valid = true;
+        let err = Postgres::new()
+            .add_foreign_index(
+                &["blubb_id".into()],
+                "blubb",
+                &["id".into()],
+                None,
+                &false,
+                &options,
+            )
+            .unwrap_err();
+        assert!(matches!(err, SqlPressError::InvalidChange(_)));
+    }
+
+    #[test]
+    fn drop_primary_key() {
+        let d = Box::new(Postgres::new());
+        assert_eq!(
+            d.drop_primary_key("order", None).unwrap(),
+            "DROP CONSTRAINT \"order_pkey\""
+        );
+        assert_eq!(
+            d.drop_primary_key("order", Some("pk_order")).unwrap(),
+            "DROP CONSTRAINT \"pk_order\""
+        );
+    }
+
+    #[test]
+    fn rename_constraint() {
+        let d = Box::new(Postgres::new());
+        assert_eq!(
+            d.rename_constraint("order_user_id_fkey", "fk_order_user")
+                .unwrap(),
+            "RENAME CONSTRAINT \"order_user_id_fkey\" TO \"fk_order_user\""
+        );
+    }
+
+    #[test]
+    fn drop_index() {
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .drop_index(Some("users"), "idx_users_email", false, false)
+            .unwrap();
+        assert_eq!(ddl, "DROP INDEX \"public\".\"idx_users_email\";");
+        let ddl = d.drop_index(None, "idx_users_email", true, true).unwrap();
+        assert_eq!(
+            ddl,
+            "DROP INDEX CONCURRENTLY IF EXISTS \"public\".\"idx_users_email\";"
+        );
+    }
+
+    #[test]
+    fn reindex() {
+        let d = Box::new(Postgres::new());
+        assert_eq!(
+            d.reindex_index("idx_users_email", false).unwrap(),
+            "REINDEX INDEX \"public\".\"idx_users_email\";"
+        );
+        assert_eq!(
+            d.reindex_index("idx_users_email", true).unwrap(),
+            "REINDEX INDEX CONCURRENTLY \"public\".\"idx_users_email\";"
+        );
+        assert_eq!(
+            d.reindex_table("users").unwrap(),
+            "REINDEX TABLE \"public\".\"users\";"
+        );
+    }
+
+    #[test]
+    fn replace_primary_key_concurrently() {
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .replace_primary_key_concurrently("orders", &["tenant_id".into(), "id".into()], None)
+            .unwrap();
+        assert_eq!(
+            ddl,
+            r#"CREATE UNIQUE INDEX CONCURRENTLY "orders_pkey_new" ON "public"."orders" ("tenant_id", "id");
+
+ALTER TABLE "public"."orders"
+DROP CONSTRAINT "orders_pkey",
+ADD CONSTRAINT "orders_pkey" PRIMARY KEY USING INDEX "orders_pkey_new";"#
+        );
+
+        let ddl = d
+            .replace_primary_key_concurrently("orders", &["id".into()], Some("pk_orders"))
+            .unwrap();
+        assert_eq!(
+            ddl,
+            r#"CREATE UNIQUE INDEX CONCURRENTLY "pk_orders_new" ON "public"."orders" ("id");
+
+ALTER TABLE "public"."orders"
+DROP CONSTRAINT "pk_orders",
+ADD CONSTRAINT "pk_orders" PRIMARY KEY USING INDEX "pk_orders_new";"#
+        );
+    }
+
+    #[test]
+    fn quote_ident() {
+        let d = Postgres::new();
+        assert_eq!(
+            d.drop_table("my\"table", false, false).unwrap(),
+            "DROP TABLE \"public\".\"my\"\"table\";"
+        );
+    }
+
+    #[test]
+    fn quote_ident_too_long() {
+        let d = Postgres::new();
+        let err = d.quote_ident(&Ident::new(&"x".repeat(64))).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Postgres identifiers must not be longer than 63 bytes"));
+    }
+
+    #[test]
+    fn ensure_uuid_generation() {
+        let mut constraints = Constraints::new();
+        constraints.default = DefaultConstraint::RandomUuid;
+
+        let d = Postgres::new();
+        assert_eq!(d.ensure_uuid_generation().unwrap(), "");
+        assert_eq!(
+            d.constraints(&constraints).unwrap(),
+            " DEFAULT gen_random_uuid()"
+        );
+
+        let d = Postgres::new().with_uuid_generation(UuidGeneration::UuidOssp);
+        assert_eq!(
+            d.ensure_uuid_generation().unwrap(),
+            "CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\";"
+        );
+        assert_eq!(
+            d.constraints(&constraints).unwrap(),
+            " DEFAULT uuid_generate_v4()"
+        );
+    }
+
+    #[test]
+    fn unique_nulls_not_distinct() {
+        let mut constraints = Constraints::new();
+        constraints.unique = true;
+        constraints.nulls_not_distinct = true;
+        assert_eq!(
+            Postgres::new().constraints(&constraints).unwrap(),
+            " UNIQUE NULLS NOT DISTINCT"
+        );
+
+        constraints.unique_name = Some("uq_user_external_id".into());
+        assert_eq!(
+            Postgres::new()
+                .with_version(15)
+                .constraints(&constraints)
+                .unwrap(),
+            " CONSTRAINT \"uq_user_external_id\" UNIQUE NULLS NOT DISTINCT"
+        );
+    }
+
+    #[test]
+    fn unique_nulls_not_distinct_version() {
+        let mut constraints = Constraints::new();
+        constraints.unique = true;
+        constraints.nulls_not_distinct = true;
+        assert_eq!(
+            Postgres::new().with_version(14).constraints(&constraints),
+            Err(SqlPressError::UnsupportedByDialect(
+                "UNIQUE NULLS NOT DISTINCT requires postgres 15".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn typed_defaults() {
+        let d = Postgres::new();
+        let mut constraints = Constraints::new();
+        constraints.default = DefaultConstraint::CurrentTimestamp;
+        assert_eq!(d.constraints(&constraints).unwrap(), " DEFAULT now()");
+
+        constraints.default = DefaultConstraint::value("it's");
+        assert_eq!(d.constraints(&constraints).unwrap(), " DEFAULT 'it''s'");
+    }
+
+    #[test]
+    fn for_schema() {
+        let d = Postgres::new().for_schema("tenant_a");
+        assert_eq!(
+            d.drop_table("tag", false, false).unwrap(),
+            "DROP TABLE \"tenant_a\".\"tag\";"
+        );
+    }
+
+    #[test]
+    fn quotes_schema() {
+        let d = Postgres::new().with_schema("tenant-A");
+        assert_eq!(
+            d.drop_table("tag", false, false).unwrap(),
+            "DROP TABLE \"tenant-A\".\"tag\";"
+        );
+        assert_eq!(
+            d.for_schema("Tenant\"B")
+                .drop_table("tag", false, false)
+                .unwrap(),
+            "DROP TABLE \"Tenant\"\"B\".\"tag\";"
+        );
+    }
+
+    #[test]
+    fn create_composite_type() {
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .create_composite_type(
+                "address",
+                &[
+                    ("street".into(), ColumnType::TEXT),
+                    ("zip".into(), ColumnType::VARCHAR(Some(10))),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            "CREATE TYPE \"public\".\"address\" AS (\n\"street\" text,\n\"zip\" VARCHAR(10)\n);"
+        );
+    }
+
+    #[test]
+    fn rename_type() {
+        let d = Box::new(Postgres::new());
+        let ddl = d.rename_type("address", "postal_address").unwrap();
+        assert_eq!(
+            ddl,
+            "ALTER TYPE \"public\".\"address\" RENAME TO \"postal_address\";"
+        );
+    }
+
+    #[test]
+    fn drop_type() {
+        let d = Box::new(Postgres::new());
+        assert_eq!(
+            d.drop_type("address").unwrap(),
+            "DROP TYPE \"public\".\"address\";"
+        );
+        assert_eq!(
+            d.column_type(&ColumnType::Composite("address".into()))
+                .unwrap(),
+            "\"public\".\"address\""
+        );
+    }
+
+    #[test]
+    fn lock_timeout() {
+        let d = Box::new(Postgres::new());
+        let ddl = d.lock_timeout(Duration::from_millis(2500)).unwrap();
+        assert_eq!(ddl, "SET LOCAL lock_timeout = '2500ms';");
+    }
+
+    #[test]
+    fn only_if() {
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .only_if(
+                &Precondition::TableExists("tags".into()),
+                "DROP TABLE \"public\".\"tags\";",
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            r#"DO $sql_press$
+BEGIN
+IF EXISTS (SELECT 1 FROM information_schema.tables WHERE table_schema = 'public' AND table_name = 'tags') THEN
+DROP TABLE "public"."tags";
+END IF;
+END
+$sql_press$;"#
+        );
+
+        let ddl = d
+            .only_if(
+                &Precondition::ColumnMissing("tags".into(), "slug".into()),
+                "ALTER TABLE \"public\".\"tags\"\nADD COLUMN \"slug\" text;",
+            )
+            .unwrap();
+        assert_eq!(
+            ddl,
+            r#"DO $sql_press$
+BEGIN
+IF NOT EXISTS (SELECT 1 FROM information_schema.columns WHERE table_schema = 'public' AND table_name = 'tags' AND column_name = 'slug') THEN
+ALTER TABLE "public"."tags"
+ADD COLUMN "slug" text;
+END IF;
+END
+$sql_press$;"#
+        );
+    }
+
+    #[test]
+    fn only_if_escapes_literals() {
+        let d = Box::new(Postgres::new().with_schema("o'brien"));
+        let ddl = d
+            .only_if(
+                &Precondition::ColumnExists("it's".into(), "o'clock".into()),
+                "SELECT 1;",
+            )
+            .unwrap();
+        assert!(ddl.contains(
+            "WHERE table_schema = 'o''brien' AND table_name = 'it''s' AND column_name = 'o''clock'"
+        ));
+    }
+
+    #[test]
+    fn only_if_avoids_dollar_quote_tag_of_body() {
+        let d = Box::new(Postgres::new());
+        let ddl = d
+            .only_if(
+                &Precondition::TableExists("tags".into()),
+                "SELECT $sql_press$a$sql_press$, $sql_press_1$b$sql_press_1$;",
+            )
+            .unwrap();
+        assert!(ddl.starts_with("DO $sql_press_2$\n"));
+        assert!(ddl.ends_with("\n$sql_press_2$;"));
+    }
+
+    #[derive(Debug)]
+    struct HouseRules;
+
+    impl TypeMapping for HouseRules {
+        fn map_type(&self, ct: &ColumnType) -> Option<String> {
+            match ct {
+                ColumnType::VARCHAR(_) => Some("citext".into()),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn type_mapping() {
+        let d = Box::new(Postgres::new().with_type_mapping(HouseRules));
+        assert_eq!(
+            d.column_type(&ColumnType::VARCHAR(Some(255))).unwrap(),
+            "citext"
+        );
+        assert_eq!(d.column_type(&ColumnType::UUID).unwrap(), "uuid");
+
+        let ddl = d
+            .add_column(
+                "name",
+                false,
+                &ColumnType::VARCHAR(Some(100)),
+                &Constraints::new(),
+            )
+            .unwrap();
+        assert_eq!(ddl, "\"name\" citext");
     }
 }
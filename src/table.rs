@@ -1,29 +1,117 @@
 //! Provides all operations where a full SQL table is changed.
-use std::rc::Rc;
+use std::{mem, rc::Rc};
 
 use crate::{
     change::{Change, Changes},
+    column::{
+        ColumnAddChange, ColumnAlterChange, ColumnDropChange, ColumnRenameChange, ColumnType,
+    },
+    error::{Result, SqlPressError},
+    expr::{Expr, Value},
+    index::{
+        IndexAddCombinedChange, IndexAddForeignChange, IndexAddPrimaryChange, IndexAddUniqueChange,
+    },
+    naming::NamingStrategy,
     sql_dialect::SqlDialect,
 };
 
-/// Contains all column and index changes for a table.
+/// Contains all column and index changes for a table. Independent of the
+/// order in which they were added, changes are rendered in the following
+/// order, so the resulting statement is always valid:
+/// 1. dropped constraints (dropping a column also drops its constraints),
+/// 2. column changes,
+/// 3. added indexes and constraints (which may refer to added columns).
 pub struct Table {
+    pub(crate) name: String,
+    pub(crate) drop_changes: Changes,
     pub(crate) changes: Changes,
     pub(crate) idx_changes: Changes,
+    /// Standalone statements, which have to be executed before the table
+    /// statement (e.g. `CREATE TYPE` for enum columns on postgres).
+    pub(crate) pre_statements: Changes,
+    /// Standalone statements, which have to be executed after the table
+    /// statement (e.g. `DROP INDEX` on postgres).
+    pub(crate) statements: Changes,
+    /// Explicit order of added columns (see
+    /// [ColumnCreate::column_order][crate::column::ColumnCreate::column_order]).
+    pub(crate) column_order: Vec<String>,
+    /// Names the constraints, which are added without a name (see
+    /// [ChangeSet::naming_strategy][crate::change::ChangeSet::naming_strategy]).
+    pub(crate) naming_strategy: Option<Rc<dyn NamingStrategy>>,
 }
 
 impl Table {
     pub fn new() -> Self {
         Self {
+            name: String::new(),
+            drop_changes: Vec::new(),
             changes: Vec::new(),
             idx_changes: Vec::new(),
+            pre_statements: Vec::new(),
+            statements: Vec::new(),
+            column_order: Vec::new(),
+            naming_strategy: None,
         }
     }
 
-    pub fn get_changes(self) -> Changes {
-        self.changes
+    pub(crate) fn named(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Names the foreign key with the naming strategy, unless it is named
+    /// already.
+    pub(crate) fn name_foreign_key(&self, foreign_key: &mut IndexAddForeignChange) {
+        if let (None, Some(n)) = (&foreign_key.idx_name, &self.naming_strategy) {
+            foreign_key.idx_name = Some(n.foreign_key(&self.name, &foreign_key.columns));
+        }
+    }
+
+    /// Index on the given columns of this table, which is named with the
+    /// naming strategy, unless `idx_name` is given.
+    pub(crate) fn index(&self, columns: &[&str], idx_name: Option<&str>) -> IndexAddCombinedChange {
+        let mut index = IndexAddCombinedChange::new(&self.name, columns.to_vec());
+        index.idx_name = match (idx_name, &self.naming_strategy) {
+            (Some(n), _) => Some(n.into()),
+            (None, Some(n)) => Some(n.index(&self.name, &index.column_names())),
+            (None, None) => None,
+        };
+        index
+    }
+
+    /// Names the inline unique constraint of the column with the naming
+    /// strategy.
+    pub(crate) fn name_column_constraints(&self, column: &mut ColumnAddChange) {
+        let c = &mut column.constraints;
+        if let (true, None, Some(n)) = (c.unique, &c.unique_name, &self.naming_strategy) {
+            c.unique_name = Some(n.unique(&self.name, std::slice::from_ref(&column.name)));
+        }
+    }
+
+    /// Takes the standalone statements, which have to be executed before and
+    /// after the table statement.
+    pub(crate) fn take_statements(&mut self) -> (Changes, Changes) {
+        (
+            std::mem::take(&mut self.pre_statements),
+            std::mem::take(&mut self.statements),
+        )
+    }
+
+    pub fn get_changes(mut self) -> Changes {
+        let order = &self.column_order;
+        self.changes.sort_by_key(|c| {
+            c.as_any()
+                .downcast_ref::<ColumnAddChange>()
+                .and_then(|col| order.iter().position(|o| *o == col.name))
+                .unwrap_or(order.len())
+        });
+
+        self.drop_changes
             .into_iter()
-            .chain(self.idx_changes.into_iter())
+            .chain(self.changes)
+            .chain(self.idx_changes)
             .collect()
     }
 }
@@ -38,18 +126,20 @@ impl Default for Table {
 #[derive(Debug)]
 pub enum TableChangeOp {
     Create,
-    // TODO: unused so far
     CreateIfNotExists,
     Alter,
+    AlterIfExists,
     Rename { new_table_name: String },
-    Drop,
+    Drop { if_exists: bool, cascade: bool },
 }
 
 #[derive(Debug)]
 pub struct TableChange {
     operation: TableChangeOp,
-    name: String,
+    pub(crate) name: String,
     changes: Changes,
+    pre_statements: Changes,
+    statements: Changes,
 }
 
 impl TableChange {
@@ -63,19 +153,135 @@ impl TableChange {
             operation,
             name,
             changes,
+            pre_statements: Vec::new(),
+            statements: Vec::new(),
         })
     }
-}
 
-impl Change for TableChange {
-    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> String {
+    /// Adds standalone statements, which are rendered before and after the
+    /// table statement itself.
+    pub(crate) fn with_statements(
+        mut self: Box<Self>,
+        (pre_statements, statements): (Changes, Changes),
+    ) -> Box<Self> {
+        self.pre_statements = pre_statements;
+        self.statements = statements;
+        self
+    }
+
+    /// Columns of every index (primary key, unique constraint, index) added
+    /// to this table.
+    pub(crate) fn indexed_columns(&self) -> Vec<Vec<String>> {
+        self.changes
+            .iter()
+            .chain(self.statements.iter())
+            .filter_map(|c| {
+                let c = c.as_any();
+                if let Some(col) = c.downcast_ref::<ColumnAddChange>() {
+                    (col.constraints.primary || col.constraints.unique)
+                        .then(|| vec![col.name.clone()])
+                } else if let Some(idx) = c.downcast_ref::<IndexAddPrimaryChange>() {
+                    Some(idx.columns.clone())
+                } else if let Some(idx) = c.downcast_ref::<IndexAddUniqueChange>() {
+                    Some(idx.columns.clone())
+                } else {
+                    c.downcast_ref::<IndexAddCombinedChange>()
+                        .map(|idx| idx.column_names())
+                }
+            })
+            .collect()
+    }
+
+    /// Applies the column changes of this table change to the given columns
+    /// (name and type) of the table.
+    pub(crate) fn apply_columns(&self, columns: &mut Vec<(String, ColumnType)>) {
+        if matches!(self.operation, TableChangeOp::Drop { .. }) {
+            columns.clear();
+        }
+
+        for c in self.changes.iter() {
+            let c = c.as_any();
+            if let Some(col) = c.downcast_ref::<ColumnAddChange>() {
+                columns.push((col.name.clone(), col.ct.clone()));
+            } else if let Some(col) = c.downcast_ref::<ColumnDropChange>() {
+                columns.retain(|(n, _)| *n != col.name);
+            } else if let Some(col) = c.downcast_ref::<ColumnRenameChange>() {
+                columns
+                    .iter_mut()
+                    .filter(|(n, _)| *n == col.name)
+                    .for_each(|(n, _)| *n = col.new_name.clone());
+            } else if let Some(col) = c.downcast_ref::<ColumnAlterChange>() {
+                columns
+                    .iter_mut()
+                    .filter(|(n, _)| *n == col.name)
+                    .for_each(|(_, ct)| *ct = col.ct.clone());
+            }
+        }
+    }
+
+    pub(crate) fn is_create(&self) -> bool {
+        matches!(
+            self.operation,
+            TableChangeOp::Create | TableChangeOp::CreateIfNotExists
+        )
+    }
+
+    /// New name of the table, if this change renames it.
+    pub(crate) fn new_name(&self) -> Option<&str> {
+        match &self.operation {
+            TableChangeOp::Rename { new_table_name } => Some(new_table_name),
+            _ => None,
+        }
+    }
+
+    /// Referencing columns of all foreign keys added to this table.
+    pub(crate) fn foreign_key_columns(&self) -> Vec<Vec<String>> {
+        self.changes
+            .iter()
+            .filter_map(|c| c.as_any().downcast_ref::<IndexAddForeignChange>())
+            .map(|fk| fk.columns.clone())
+            .collect()
+    }
+
+    /// Renders the `ALTER TABLE` statement, which is split at column
+    /// renames, since those have to be standalone statements.
+    fn get_alter_table_ddl(&self, dialect: Rc<dyn SqlDialect>, if_exists: bool) -> Result<String> {
+        let mut statements = Vec::new();
+        let mut changes = Vec::new();
+        for c in self.changes.iter() {
+            if let Some(rename) = c.as_any().downcast_ref::<ColumnRenameChange>() {
+                if !changes.is_empty() {
+                    statements.push(dialect.alter_table(
+                        &self.name,
+                        mem::take(&mut changes),
+                        if_exists,
+                    )?);
+                }
+                statements.push(dialect.rename_column(
+                    &self.name,
+                    &rename.name,
+                    &rename.new_name,
+                    if_exists,
+                )?);
+            } else {
+                changes.push(c.get_ddl(dialect.clone())?);
+            }
+        }
+        if !changes.is_empty() || statements.is_empty() {
+            statements.push(dialect.alter_table(&self.name, changes, if_exists)?);
+        }
+
+        Ok(statements.join("\n\n"))
+    }
+
+    fn get_table_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
         match &self.operation {
             TableChangeOp::Create => {
                 let c = self
                     .changes
                     .iter()
                     .map(|c| c.get_ddl(dialect.clone()))
-                    .collect();
+                    .collect::<Result<Vec<String>>>()?;
                 dialect.create_table(&self.name, c, false)
             }
             TableChangeOp::CreateIfNotExists => {
@@ -83,21 +289,213 @@ impl Change for TableChange {
                     .changes
                     .iter()
                     .map(|c| c.get_ddl(dialect.clone()))
-                    .collect();
+                    .collect::<Result<Vec<String>>>()?;
                 dialect.create_table(&self.name, c, true)
             }
-            TableChangeOp::Alter => {
-                let c = self
-                    .changes
-                    .iter()
-                    .map(|c| c.get_ddl(dialect.clone()))
-                    .collect();
-                dialect.alter_table(&self.name, c)
+            TableChangeOp::Alter => self.get_alter_table_ddl(dialect, false),
+            TableChangeOp::AlterIfExists => self.get_alter_table_ddl(dialect, true),
+            TableChangeOp::Drop { if_exists, cascade } => {
+                dialect.drop_table(&self.name, *if_exists, *cascade)
             }
-            TableChangeOp::Drop => dialect.drop_table(&self.name),
             TableChangeOp::Rename { new_table_name } => {
                 dialect.rename_table(&self.name, new_table_name)
             }
         }
     }
 }
+
+impl Change for TableChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        // an `ALTER TABLE` without any changes is invalid, which happens if
+        // only standalone statements were defined
+        let skip_table = matches!(
+            self.operation,
+            TableChangeOp::Alter | TableChangeOp::AlterIfExists
+        ) && self.changes.is_empty()
+            && !self.statements.is_empty();
+
+        let pre_statements = self
+            .pre_statements
+            .iter()
+            .map(|c| c.get_ddl(dialect.clone()));
+
+        Ok(pre_statements
+            .chain((!skip_table).then(|| self.get_table_ddl(dialect.clone())))
+            .chain(self.statements.iter().map(|c| c.get_ddl(dialect.clone())))
+            .collect::<Result<Vec<String>>>()?
+            .into_iter()
+            .filter(|ddl| !ddl.is_empty())
+            .collect::<Vec<String>>()
+            .join("\n\n"))
+    }
+
+    fn is_lock_heavy(&self) -> bool {
+        !matches!(
+            self.operation,
+            TableChangeOp::Create | TableChangeOp::CreateIfNotExists
+        )
+    }
+
+    fn is_transactional(&self) -> bool {
+        self.pre_statements
+            .iter()
+            .chain(self.changes.iter())
+            .chain(self.statements.iter())
+            .all(|c| c.is_transactional())
+    }
+}
+
+/// Creates a history table for a table, together with a trigger, which
+/// records every inserted, updated and deleted row.
+#[derive(Debug)]
+pub struct AuditHistoryChange {
+    table_name: String,
+    /// Columns of the table, `None` if they are unknown
+    columns: Option<Vec<(String, ColumnType)>>,
+}
+
+impl AuditHistoryChange {
+    pub fn new(table_name: &str, columns: Option<Vec<(String, ColumnType)>>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            columns,
+        }
+    }
+}
+
+impl Change for AuditHistoryChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        match &self.columns {
+            Some(columns) => dialect.audit_history(&self.table_name, columns),
+            None => Err(SqlPressError::invalid(format!(
+                "the audit history of {} requires the table to be created in the same ChangeSet",
+                self.table_name
+            ))),
+        }
+    }
+}
+
+/// Inserts rows into a table, e.g. to seed lookup tables.
+#[derive(Debug)]
+pub struct RowsInsertChange {
+    table_name: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl RowsInsertChange {
+    pub fn new(table_name: &str, columns: &[&str], rows: Vec<Vec<Value>>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows,
+        }
+    }
+}
+
+impl Change for RowsInsertChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.insert_rows(&self.table_name, &self.columns, &self.rows)
+    }
+}
+
+/// Drops multiple tables at once.
+#[derive(Debug)]
+pub struct TablesDropChange {
+    names: Vec<String>,
+    cascade: bool,
+}
+
+impl TablesDropChange {
+    pub fn new(names: &[&str], cascade: bool) -> Self {
+        Self {
+            names: names.iter().map(|n| n.to_string()).collect(),
+            cascade,
+        }
+    }
+}
+
+impl Change for TablesDropChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.drop_tables(&self.names, self.cascade)
+    }
+
+    fn is_lock_heavy(&self) -> bool {
+        true
+    }
+}
+
+/// Bound of a partition, which defines the rows stored in it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartitionBound {
+    /// Range partition from the inclusive lower to the exclusive upper bound
+    /// (`FOR VALUES FROM (...) TO (...)`), one value per partition key column
+    Range { from: Vec<Expr>, to: Vec<Expr> },
+    /// List partition (`FOR VALUES IN (...)`)
+    List(Vec<Expr>),
+    /// Hash partition (`FOR VALUES WITH (MODULUS m, REMAINDER r)`)
+    Hash { modulus: u32, remainder: u32 },
+    /// Default partition for all rows, which don't fit any other partition
+    Default,
+}
+
+/// Attaches an existing table as partition to a partitioned table.
+#[derive(Debug)]
+pub struct PartitionAttachChange {
+    table_name: String,
+    partition_name: String,
+    bound: PartitionBound,
+}
+
+impl PartitionAttachChange {
+    pub fn new(table_name: &str, partition_name: &str, bound: PartitionBound) -> Self {
+        Self {
+            table_name: table_name.into(),
+            partition_name: partition_name.into(),
+            bound,
+        }
+    }
+}
+
+impl Change for PartitionAttachChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.attach_partition(&self.table_name, &self.partition_name, &self.bound)
+    }
+
+    fn is_lock_heavy(&self) -> bool {
+        true
+    }
+}
+
+/// Detaches a partition from its partitioned table, which keeps existing as
+/// a regular table.
+#[derive(Debug)]
+pub struct PartitionDetachChange {
+    table_name: String,
+    partition_name: String,
+    concurrently: bool,
+}
+
+impl PartitionDetachChange {
+    pub fn new(table_name: &str, partition_name: &str, concurrently: bool) -> Self {
+        Self {
+            table_name: table_name.into(),
+            partition_name: partition_name.into(),
+            concurrently,
+        }
+    }
+}
+
+impl Change for PartitionDetachChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        dialect.detach_partition(&self.table_name, &self.partition_name, self.concurrently)
+    }
+
+    fn is_lock_heavy(&self) -> bool {
+        !self.concurrently
+    }
+
+    fn is_transactional(&self) -> bool {
+        !self.concurrently
+    }
+}
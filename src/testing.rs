@@ -0,0 +1,190 @@
+//! Helpers for asserting generated DDL in tests. All comparisons are done on a
+//! normalized form of the DDL, so tests don't break on whitespace, identifier
+//! quoting or keyword casing.
+//!
+//! With the `test-utils` feature, the [conformance] suite verifies custom
+//! [SqlDialect][crate::sql_dialect::SqlDialect] implementations.
+
+#[cfg(feature = "test-utils")]
+pub mod conformance;
+
+/// Normalizes the given DDL by collapsing whitespace, removing identifier
+/// quotes (`"` and `[]`) and upper-casing everything outside of string
+/// literals.
+///
+/// # Example
+/// ```
+/// use sql_press::testing::normalize_ddl;
+///
+/// assert_eq!(
+///     normalize_ddl("create table \"public\".\"tag\" (\n  \"id\" uuid\n);"),
+///     "CREATE TABLE PUBLIC.TAG(ID UUID);"
+/// );
+/// ```
+pub fn normalize_ddl(ddl: &str) -> String {
+    let mut normalized = String::with_capacity(ddl.len());
+    let mut in_literal = false;
+    let mut pending_space = false;
+
+    for c in ddl.chars() {
+        if in_literal {
+            normalized.push(c);
+            in_literal = c != '\'';
+            continue;
+        }
+
+        match c {
+            '"' | '[' | ']' => continue,
+            c if c.is_whitespace() => {
+                pending_space = true;
+                continue;
+            }
+            _ => {}
+        }
+
+        if pending_space && !normalized.is_empty() {
+            let last = normalized.chars().last().unwrap_or(' ');
+            if !is_punctuation(last) && !is_punctuation(c) {
+                normalized.push(' ');
+            }
+        }
+        pending_space = false;
+
+        in_literal = c == '\'';
+        normalized.extend(c.to_uppercase());
+    }
+
+    normalized
+}
+
+fn is_punctuation(c: char) -> bool {
+    matches!(c, '(' | ')' | ',' | ';')
+}
+
+/// Splits normalized DDL into its individual statements, without the
+/// terminating semicolon.
+fn statements(ddl: &str) -> Vec<String> {
+    normalize_ddl(ddl)
+        .split(';')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Asserts that both DDL strings are equal after normalization (see
+/// [normalize_ddl]).
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "postgres")] {
+/// use sql_press::{change::ChangeSet, sql_dialect::Postgres, testing::assert_ddl_eq};
+///
+/// let mut cs = ChangeSet::new();
+/// cs.drop_table("my_table");
+///
+/// assert_ddl_eq(&cs.get_ddl(Postgres::new_rc()).unwrap(), "drop table public.my_table;");
+/// # }
+/// ```
+#[track_caller]
+pub fn assert_ddl_eq(actual: &str, expected: &str) {
+    let normalized_actual = normalize_ddl(actual);
+    let normalized_expected = normalize_ddl(expected);
+
+    assert!(
+        normalized_actual == normalized_expected,
+        "DDL mismatch\n  actual: {}\nexpected: {}\n\nnormalized actual: {}\nnormalized expected: {}",
+        actual,
+        expected,
+        normalized_actual,
+        normalized_expected
+    );
+}
+
+/// Asserts that the given DDL contains the given statement after
+/// normalization (see [normalize_ddl]).
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "postgres")] {
+/// use sql_press::{change::ChangeSet, sql_dialect::Postgres, testing::assert_contains_statement};
+///
+/// let mut cs = ChangeSet::new();
+/// cs.run_script("CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\";");
+/// cs.drop_table("my_table");
+///
+/// assert_contains_statement(&cs.get_ddl(Postgres::new_rc()).unwrap(), "DROP TABLE public.my_table");
+/// # }
+/// ```
+#[track_caller]
+pub fn assert_contains_statement(ddl: &str, statement: &str) {
+    let expected = statements(statement);
+    assert!(
+        expected.len() == 1,
+        "Expected exactly one statement, got {}: {}",
+        expected.len(),
+        statement
+    );
+
+    let actual = statements(ddl);
+    assert!(
+        actual.contains(&expected[0]),
+        "DDL does not contain statement\nstatement: {}\n      ddl: {}",
+        statement,
+        ddl
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_whitespace_and_case() {
+        assert_eq!(
+            normalize_ddl(
+                "ALTER TABLE \"public\".\"tags\"\nADD COLUMN \"id\" uuid,\n  DROP COLUMN \"x\";"
+            ),
+            "ALTER TABLE PUBLIC.TAGS ADD COLUMN ID UUID,DROP COLUMN X;"
+        );
+        assert_eq!(
+            normalize_ddl("PRIMARY KEY ( \"id\", \"id2\" )"),
+            normalize_ddl("primary key(id,id2)")
+        );
+        assert_eq!(normalize_ddl("[dbo].[tag]"), "DBO.TAG");
+    }
+
+    #[test]
+    fn normalize_keeps_literals() {
+        assert_eq!(
+            normalize_ddl("DEFAULT   'Hello  World'"),
+            "DEFAULT 'Hello  World'"
+        );
+    }
+
+    #[test]
+    fn ddl_eq() {
+        assert_ddl_eq(
+            "CREATE TABLE \"public\".\"tag\" (\n\"id\" uuid\n);",
+            "create table public.tag (id uuid);",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "DDL mismatch")]
+    fn ddl_not_eq() {
+        assert_ddl_eq("DROP TABLE \"public\".\"tag\";", "DROP TABLE public.tags;");
+    }
+
+    #[test]
+    fn contains_statement() {
+        let ddl = "DROP TABLE \"public\".\"a\";\n\nDROP TABLE \"public\".\"b\";";
+        assert_contains_statement(ddl, "drop table public.b");
+        assert_contains_statement(ddl, "drop table public.a;");
+    }
+
+    #[test]
+    #[should_panic(expected = "DDL does not contain statement")]
+    fn not_contains_statement() {
+        assert_contains_statement("DROP TABLE \"public\".\"a\";", "DROP TABLE public.b");
+    }
+}
@@ -0,0 +1,469 @@
+//! Conformance suite for [SqlDialect] implementations. It renders every kind
+//! of change the crate can generate with the given dialect and checks, that
+//! the resulting DDL is well-formed. This allows dialects outside of this
+//! crate to be verified against the same set of changes as the built-in ones.
+//!
+//! Dialects signal unsupported changes by returning an error (see
+//! [SqlPressError][crate::error::SqlPressError]), those are reported as
+//! [Outcome::Unsupported] and do not fail the suite.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "postgres")] {
+//! use sql_press::{
+//!     sql_dialect::Postgres,
+//!     testing::conformance::{assert_conformance, check_dialect},
+//! };
+//!
+//! assert_conformance(Postgres::new_rc());
+//! assert!(check_dialect(Postgres::new_rc()).unsupported().is_empty());
+//! # }
+//! ```
+use std::{rc::Rc, time::Duration};
+
+use crate::{
+    change::ChangeSet,
+    column::{integer, text, uuid, varchar, ColumnType, Storage},
+    expr::Expr,
+    index::{unique_constraint, ForeignKeyBuilder, IndexBuilder},
+    precondition::Precondition,
+    sql_dialect::SqlDialect,
+    table::PartitionBound,
+};
+
+/// Result of a single conformance case.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Passed,
+    /// The dialect returned an error with the given message
+    Unsupported(String),
+    /// The dialect rendered malformed DDL, described by the given message
+    Invalid(String),
+}
+
+/// Outcomes of all conformance cases of a dialect.
+#[derive(Debug, Clone)]
+pub struct ConformanceReport {
+    pub results: Vec<(&'static str, Outcome)>,
+}
+
+impl ConformanceReport {
+    /// Returns `true` if no case rendered malformed DDL.
+    pub fn is_conformant(&self) -> bool {
+        self.invalid().is_empty()
+    }
+
+    /// Names of all cases, which are not supported by the dialect.
+    pub fn unsupported(&self) -> Vec<&'static str> {
+        self.filter(|o| matches!(o, Outcome::Unsupported(_)))
+    }
+
+    /// Names of all cases, which rendered malformed DDL.
+    pub fn invalid(&self) -> Vec<&'static str> {
+        self.filter(|o| matches!(o, Outcome::Invalid(_)))
+    }
+
+    fn filter<F: Fn(&Outcome) -> bool>(&self, f: F) -> Vec<&'static str> {
+        self.results
+            .iter()
+            .filter(|(_, o)| f(o))
+            .map(|(n, _)| *n)
+            .collect()
+    }
+}
+
+/// Single conformance case, which adds the changes to check to the
+/// [ChangeSet]. Cases with `may_be_empty` are allowed to render no DDL at all.
+struct Case {
+    name: &'static str,
+    may_be_empty: bool,
+    build: fn(&mut ChangeSet),
+}
+
+const fn case(name: &'static str, build: fn(&mut ChangeSet)) -> Case {
+    Case {
+        name,
+        may_be_empty: false,
+        build,
+    }
+}
+
+const CASES: &[Case] = &[
+    case("create_table", |cs| {
+        cs.create_table("tag", |t| {
+            t.add_column(uuid("id").primary(true).build());
+            t.add_column(
+                varchar("name", Some(255))
+                    .not_null(true)
+                    .unique(true)
+                    .build(),
+            );
+            t.add_column(text("description").build());
+        })
+    }),
+    case("create_table_if_not_exists", |cs| {
+        cs.create_table_if_not_exists("tag", |t| {
+            t.add_column(uuid("id").primary(true).build());
+        })
+    }),
+    case("create_table_with_constraints", |cs| {
+        cs.create_table("tag_user", |t| {
+            t.add_column(uuid("tag_id").not_null(true).build());
+            t.add_column(uuid("user_id").not_null(true).build());
+            t.add_primary_index(vec!["tag_id", "user_id"]);
+            t.add_unique_constraint("tag_user_uq", vec!["user_id", "tag_id"]);
+            t.add_foreign_key(ForeignKeyBuilder::new("tag_id", "tag", "id").build());
+        })
+    }),
+    case("add_column", |cs| {
+        cs.alter_table("tag", |t| t.add_column(integer("weight").build()))
+    }),
+    case("rename_column", |cs| {
+        cs.alter_table("tag", |t| t.rename_column("name", "title"))
+    }),
+    case("alter_column", |cs| {
+        cs.alter_table("tag", |t| t.alter_column("weight", ColumnType::TEXT, None))
+    }),
+    case("column_storage", |cs| {
+        cs.alter_table("tag", |t| {
+            t.set_column_storage("description", Storage::External);
+            t.set_column_compression("description", "lz4");
+        })
+    }),
+    case("drop_column", |cs| {
+        cs.alter_table("tag", |t| {
+            t.drop_column("description");
+            t.drop_column_if_exists("weight");
+        })
+    }),
+    case("alter_table_if_exists", |cs| {
+        cs.alter_table_if_exists("tag", |t| t.drop_column("description"))
+    }),
+    case("add_foreign_key", |cs| {
+        cs.alter_table("tag", |t| {
+            t.add_foreign_key(
+                ForeignKeyBuilder::new("parent_id", "tag", "id")
+                    .name("tag_parent_fk")
+                    .build(),
+            )
+        })
+    }),
+    case("add_foreign_key_not_valid", |cs| {
+        cs.alter_table("tag", |t| {
+            t.add_foreign_key(
+                ForeignKeyBuilder::new("parent_id", "tag", "id")
+                    .name("tag_parent_fk")
+                    .not_valid(true)
+                    .build(),
+            )
+        })
+    }),
+    case("add_check_constraint_not_valid", |cs| {
+        cs.alter_table("tag", |t| {
+            t.add_check_constraint_not_valid(
+                "tag_weight_check",
+                Expr::col("weight").gt(Expr::val(0)),
+            )
+        })
+    }),
+    case("validate_constraint", |cs| {
+        cs.alter_table("tag", |t| t.validate_constraint("tag_parent_fk"))
+    }),
+    case("add_unique_nulls_not_distinct", |cs| {
+        cs.alter_table("tag", |t| {
+            t.add_column(
+                varchar("external_id", Some(64))
+                    .unique_nulls_not_distinct(true)
+                    .build(),
+            );
+            t.add_unique(
+                unique_constraint("tag_name_parent_uq", vec!["name", "parent_id"])
+                    .nulls_not_distinct(true)
+                    .build(),
+            );
+        })
+    }),
+    case("drop_unique_constraint", |cs| {
+        cs.alter_table("tag_user", |t| t.drop_unique_constraint("tag_user_uq"))
+    }),
+    case("drop_constraint", |cs| {
+        cs.alter_table("tag", |t| {
+            t.drop_constraint("tag_parent_fk");
+            t.drop_constraint_with("tag_name_check", true, false);
+        })
+    }),
+    case("rename_constraint", |cs| {
+        cs.alter_table("tag", |t| {
+            t.rename_constraint("tag_parent_fk", "fk_tag_parent")
+        })
+    }),
+    case("drop_primary_key", |cs| {
+        cs.alter_table("tag_user", |t| {
+            t.drop_primary_key(None);
+            t.add_primary_index(vec!["tag_id", "user_id"]);
+        })
+    }),
+    case("add_primary_index_named", |cs| {
+        cs.alter_table("tag_user", |t| {
+            t.add_primary_index_named("pk_tag_user", vec!["tag_id", "user_id"])
+        })
+    }),
+    case("create_index", |cs| {
+        cs.create_index(
+            IndexBuilder::new("tag", vec!["name"])
+                .name("tag_name_idx")
+                .unique(true)
+                .build(),
+        )
+    }),
+    case("create_index_if_not_exists", |cs| {
+        cs.create_index(
+            IndexBuilder::new("tag", vec!["name"])
+                .name("tag_name_idx")
+                .if_not_exists(true)
+                .build(),
+        )
+    }),
+    case("create_partial_index", |cs| {
+        cs.create_index(
+            IndexBuilder::new("tag", vec!["name"])
+                .name("tag_name_idx")
+                .where_clause(Expr::col("name").is_not_null())
+                .build(),
+        )
+    }),
+    case("rename_index", |cs| {
+        cs.rename_index("tag_name_idx", "idx_tag_name")
+    }),
+    case("drop_index", |cs| {
+        cs.alter_table("tag", |t| t.drop_index("tag_name_idx"))
+    }),
+    case("drop_index_if_exists", |cs| {
+        cs.alter_table("tag", |t| t.drop_index_with("tag_name_idx", true, false))
+    }),
+    case("add_unique_constraint_using_index", |cs| {
+        cs.alter_table("tag", |t| {
+            t.add_unique_constraint_using_index("tag_name_uq", "tag_name_idx")
+        })
+    }),
+    case("add_primary_index_using_index", |cs| {
+        cs.alter_table("tag", |t| t.add_primary_index_using_index("tag_pkey_idx"))
+    }),
+    case("attach_index_partition", |cs| {
+        cs.attach_index_partition("tag_name_idx", "tag_2024_name_idx")
+    }),
+    case("reindex_index", |cs| {
+        cs.reindex_index("tag_name_idx", false)
+    }),
+    case("reindex_table", |cs| cs.reindex_table("tag")),
+    case("replace_primary_key_concurrently", |cs| {
+        cs.replace_primary_key_concurrently("tag", vec!["id", "name"], None)
+    }),
+    case("rename_table", |cs| cs.rename_table("tag", "label")),
+    case("drop_table", |cs| cs.drop_table("tag")),
+    case("drop_table_if_exists", |cs| cs.drop_table_if_exists("tag")),
+    case("drop_table_cascade", |cs| {
+        cs.drop_table_cascade("tag", true)
+    }),
+    case("attach_partition", |cs| {
+        cs.attach_partition(
+            "tag",
+            "tag_archive",
+            PartitionBound::List(vec![Expr::val("archived")]),
+        )
+    }),
+    case("detach_partition", |cs| {
+        cs.detach_partition("tag", "tag_archive", false)
+    }),
+    case("drop_tables", |cs| {
+        cs.drop_tables(&["tag", "tag_user"], true)
+    }),
+    case("enum_column", |cs| {
+        cs.create_table("ticket", |t| {
+            t.add_enum_column("status", &["open", "closed"]);
+        })
+    }),
+    case("version_column", |cs| {
+        cs.alter_table("tag", |t| t.version_column(false))
+    }),
+    case("version_trigger", |cs| {
+        cs.alter_table("tag", |t| t.version_column(true))
+    }),
+    case("create_composite_type", |cs| {
+        cs.create_composite_type(
+            "address",
+            vec![
+                ("street", ColumnType::TEXT),
+                ("zip", ColumnType::VARCHAR(Some(10))),
+            ],
+        )
+    }),
+    case("create_domain", |cs| {
+        cs.create_domain("email", ColumnType::TEXT, |d| d.not_null(true))
+    }),
+    case("drop_domain", |cs| cs.drop_domain("email")),
+    case("alter_enum", |cs| {
+        cs.alter_enum("status", |e| {
+            e.add_value("archived").after("closed");
+        })
+    }),
+    case("rename_type", |cs| {
+        cs.rename_type("address", "postal_address")
+    }),
+    case("drop_type", |cs| cs.drop_type("address")),
+    case("create_lookup_table", |cs| {
+        cs.create_lookup_table("country", &[("DE", "Germany"), ("US", "United States")])
+    }),
+    case("add_audit_history", |cs| {
+        cs.create_table("account", |t| {
+            t.add_column(uuid("id").primary(true).build());
+            t.add_column(text("email").build());
+        });
+        cs.add_audit_history("account");
+    }),
+    case("rename_view", |cs| {
+        cs.rename_view("active_tag", "active_label")
+    }),
+    case("alter_view_owner", |cs| {
+        cs.alter_view_owner("active_tag", "admin")
+    }),
+    case("alter_view_set_options", |cs| {
+        cs.alter_view_set_options("active_tag", &[("security_barrier", "true")])
+    }),
+    case("only_if", |cs| {
+        cs.only_if(Precondition::TableExists("tag".into()), |cs| {
+            cs.drop_table("tag")
+        })
+    }),
+    case("lock_timeout", |cs| {
+        cs.lock_timeout(Duration::from_secs(2));
+        cs.alter_table("tag", |t| t.add_column(integer("weight").build()));
+    }),
+    case("create_extension", |cs| cs.create_extension("citext")),
+    Case {
+        name: "ensure_uuid_generation",
+        may_be_empty: true,
+        build: |cs| cs.ensure_uuid_generation(),
+    },
+];
+
+/// Renders all conformance cases with the given dialect.
+pub fn check_dialect(dialect: Rc<dyn SqlDialect>) -> ConformanceReport {
+    ConformanceReport {
+        results: CASES
+            .iter()
+            .map(|c| (c.name, check_case(c, dialect.clone())))
+            .collect(),
+    }
+}
+
+/// Asserts, that the given dialect renders well-formed DDL for all
+/// supported conformance cases (see [check_dialect]).
+#[track_caller]
+pub fn assert_conformance(dialect: Rc<dyn SqlDialect>) {
+    let report = check_dialect(dialect);
+    let invalid = report
+        .results
+        .iter()
+        .filter_map(|(n, o)| match o {
+            Outcome::Invalid(msg) => Some(format!("{}: {}", n, msg)),
+            _ => None,
+        })
+        .collect::<Vec<String>>();
+
+    assert!(
+        invalid.is_empty(),
+        "Dialect is not conformant\n{}",
+        invalid.join("\n")
+    );
+}
+
+fn check_case(case: &Case, dialect: Rc<dyn SqlDialect>) -> Outcome {
+    let mut cs = ChangeSet::new();
+    (case.build)(&mut cs);
+
+    match cs.get_ddl(dialect) {
+        Ok(ddl) => validate(&ddl, case.may_be_empty)
+            .map(Outcome::Invalid)
+            .unwrap_or(Outcome::Passed),
+        Err(e) => Outcome::Unsupported(e.to_string()),
+    }
+}
+
+/// Checks the DDL for basic well-formedness, returns a description of the
+/// first problem found. A trailing T-SQL batch separator (`GO`) is ignored.
+fn validate(ddl: &str, may_be_empty: bool) -> Option<String> {
+    let ddl = ddl.trim();
+    let ddl = ddl.strip_suffix("\nGO").unwrap_or(ddl);
+    if ddl.is_empty() {
+        return (!may_be_empty).then(|| "no DDL rendered".into());
+    }
+
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    for c in ddl.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[') => quote = Some(']'),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth < 0 {
+                    return Some(format!("unbalanced parentheses: {}", ddl));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if quote.is_some() {
+        Some(format!("unterminated quote: {}", ddl))
+    } else if depth != 0 {
+        Some(format!("unbalanced parentheses: {}", ddl))
+    } else if !ddl.ends_with(';') {
+        Some(format!("statement not terminated: {}", ddl))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_ddl() {
+        assert_eq!(validate("DROP TABLE \"a(\";", false), None);
+        assert_eq!(validate("", true), None);
+        assert!(validate("", false).is_some());
+        assert!(validate("CREATE TABLE a (b", false).is_some());
+        assert!(validate("DROP TABLE a", false).is_some());
+        assert!(validate("DEFAULT 'a", false).is_some());
+        assert_eq!(validate("DROP TABLE a;\nGO", false), None);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn postgres() {
+        let report = check_dialect(crate::sql_dialect::Postgres::new_rc());
+        assert!(report.is_conformant(), "{:?}", report);
+        assert!(report.unsupported().is_empty(), "{:?}", report);
+    }
+
+    #[cfg(feature = "mssql")]
+    #[test]
+    fn mssql() {
+        let report = check_dialect(crate::sql_dialect::Mssql::new_rc());
+        assert!(report.is_conformant(), "{:?}", report);
+    }
+
+    #[cfg(feature = "cockroach")]
+    #[test]
+    fn cockroach() {
+        let report = check_dialect(crate::sql_dialect::Cockroach::new_rc());
+        assert!(report.is_conformant(), "{:?}", report);
+        assert!(report.unsupported().contains(&"reindex_table"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Provides all operations on user-defined types.
+use std::rc::Rc;
+
+use crate::{change::Change, column::ColumnType, error::Result, sql_dialect::SqlDialect};
+
+/// Reflects all operations, which are supported on a user-defined type.
+#[derive(Debug)]
+pub enum TypeChangeOp {
+    /// Composite type with its fields (name and type)
+    CreateComposite {
+        fields: Vec<(String, ColumnType)>,
+    },
+    /// Enum type with its values
+    CreateEnum {
+        values: Vec<String>,
+    },
+    /// Domain type with its base type and constraints
+    CreateDomain {
+        ct: ColumnType,
+        domain: DomainCreate,
+    },
+    DropDomain,
+    /// New value of an enum type
+    AddValue {
+        value: String,
+        position: Option<EnumValuePosition>,
+    },
+    Rename {
+        new_type_name: String,
+    },
+    Drop,
+}
+
+/// Constraints of a new domain type (see
+/// [ChangeSet::create_domain][crate::change::ChangeSet::create_domain]).
+#[derive(Debug, Default)]
+pub struct DomainCreate {
+    pub(crate) not_null: bool,
+    pub(crate) checks: Vec<String>,
+}
+
+impl DomainCreate {
+    pub fn not_null(&mut self, not_null: bool) {
+        self.not_null = not_null;
+    }
+
+    /// Adds a `CHECK` constraint with the given condition, which refers to
+    /// the checked value as `VALUE`.
+    pub fn check(&mut self, condition: &str) {
+        self.checks.push(condition.into());
+    }
+}
+
+/// Position of a new enum value relative to an existing one. Without a
+/// position, the value is appended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnumValuePosition {
+    Before(String),
+    After(String),
+}
+
+/// Collects the values, which are added to an existing enum type (see
+/// [ChangeSet::alter_enum][crate::change::ChangeSet::alter_enum]).
+#[derive(Debug, Default)]
+pub struct EnumAlter {
+    pub(crate) values: Vec<EnumValueAdd>,
+}
+
+impl EnumAlter {
+    /// Adds the given value to the end of the enum, unless positioned with
+    /// [EnumValueAdd::before] or [EnumValueAdd::after].
+    pub fn add_value(&mut self, value: &str) -> &mut EnumValueAdd {
+        self.values.push(EnumValueAdd {
+            value: value.into(),
+            position: None,
+        });
+
+        self.values.last_mut().unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct EnumValueAdd {
+    pub(crate) value: String,
+    pub(crate) position: Option<EnumValuePosition>,
+}
+
+impl EnumValueAdd {
+    pub fn before(&mut self, value: &str) -> &mut Self {
+        self.position = Some(EnumValuePosition::Before(value.into()));
+
+        self
+    }
+
+    pub fn after(&mut self, value: &str) -> &mut Self {
+        self.position = Some(EnumValuePosition::After(value.into()));
+
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct TypeChange {
+    operation: TypeChangeOp,
+    name: String,
+}
+
+impl TypeChange {
+    pub fn new(operation: TypeChangeOp, name: &str) -> Self {
+        Self {
+            operation,
+            name: name.into(),
+        }
+    }
+}
+
+impl Change for TypeChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        match &self.operation {
+            TypeChangeOp::CreateComposite { fields } => {
+                dialect.create_composite_type(&self.name, fields)
+            }
+            TypeChangeOp::CreateEnum { values } => dialect.create_enum_type(&self.name, values),
+            TypeChangeOp::CreateDomain { ct, domain } => {
+                dialect.create_domain(&self.name, ct, domain.not_null, &domain.checks)
+            }
+            TypeChangeOp::DropDomain => dialect.drop_domain(&self.name),
+            TypeChangeOp::AddValue { value, position } => {
+                dialect.add_enum_value(&self.name, value, position)
+            }
+            TypeChangeOp::Rename { new_type_name } => {
+                dialect.rename_type(&self.name, new_type_name)
+            }
+            TypeChangeOp::Drop => dialect.drop_type(&self.name),
+        }
+    }
+}
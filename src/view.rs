@@ -0,0 +1,48 @@
+//! Provides all operations on existing views.
+use std::rc::Rc;
+
+use crate::{change::Change, error::Result, sql_dialect::SqlDialect};
+
+/// Reflects all operations, which are supported on an existing view.
+#[derive(Debug)]
+pub enum ViewChangeOp {
+    Rename {
+        new_view_name: String,
+    },
+    Owner {
+        role: String,
+    },
+    /// View options (e.g. `security_barrier`) as key/value pairs
+    SetOptions {
+        options: Vec<(String, String)>,
+    },
+}
+
+#[derive(Debug)]
+pub struct ViewChange {
+    operation: ViewChangeOp,
+    name: String,
+}
+
+impl ViewChange {
+    pub fn new(operation: ViewChangeOp, name: &str) -> Self {
+        Self {
+            operation,
+            name: name.into(),
+        }
+    }
+}
+
+impl Change for ViewChange {
+    fn get_ddl(&self, dialect: Rc<dyn SqlDialect>) -> Result<String> {
+        match &self.operation {
+            ViewChangeOp::Rename { new_view_name } => {
+                dialect.rename_view(&self.name, new_view_name)
+            }
+            ViewChangeOp::Owner { role } => dialect.alter_view_owner(&self.name, role),
+            ViewChangeOp::SetOptions { options } => {
+                dialect.alter_view_set_options(&self.name, options)
+            }
+        }
+    }
+}